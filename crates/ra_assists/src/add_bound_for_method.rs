@@ -0,0 +1,121 @@
+use hir::{ModuleDef, Resolution, TraitItem, Ty, db::HirDatabase};
+use ra_syntax::{
+    AstNode,
+    ast::{self, NameOwner, TypeBoundsOwner, TypeParamsOwner},
+};
+
+use crate::{AssistCtx, Assist, AssistId};
+
+/// Adds the bound that is missing for a method call on a type param, e.g. for
+/// `t.clone()` with an unbounded `T`, `fn f<T>` becomes `fn f<T: Clone>`. The
+/// receiver may be the result of a call chain, like in `t.get().clone()`; the
+/// bound is added to the param that is the type of the receiver.
+pub(crate) fn add_bound_for_method(mut ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let call = ctx.node_at_offset::<ast::MethodCallExpr>()?;
+    let method_name = call.name_ref()?.text().to_string();
+    let analyzer = hir::SourceAnalyzer::new(ctx.db, ctx.frange.file_id, call.syntax(), None);
+    if analyzer.resolve_method_call(call).is_some() {
+        return None;
+    }
+    let param_name = match analyzer.type_of(ctx.db, call.expr()?)? {
+        Ty::Param { name, .. } => name.to_string(),
+        _ => return None,
+    };
+
+    // only offer the bound if exactly one trait in scope has the method
+    let mut traits = analyzer.all_names(ctx.db).into_iter().filter(|(_, res)| {
+        let trait_ = match res.as_ref().take_types() {
+            Some(Resolution::Def(ModuleDef::Trait(it))) => *it,
+            _ => return false,
+        };
+        trait_.items(ctx.db).into_iter().any(|item| match item {
+            TraitItem::Function(f) => f.name(ctx.db).to_string() == method_name,
+            _ => false,
+        })
+    });
+    let (trait_name, _) = traits.next()?;
+    if traits.next().is_some() {
+        return None;
+    }
+
+    let type_param = call.syntax().ancestors().find_map(|node| {
+        let type_params = ast::FnDef::cast(node)
+            .and_then(|it| it.type_param_list())
+            .or_else(|| ast::ImplBlock::cast(node).and_then(|it| it.type_param_list()))
+            .or_else(|| ast::TraitDef::cast(node).and_then(|it| it.type_param_list()))?;
+        type_params
+            .type_params()
+            .find(|it| it.name().map(|n| n.text().to_string()) == Some(param_name.clone()))
+    })?;
+
+    ctx.add_action(AssistId("add_bound_for_method"), "add missing bound", |edit| {
+        edit.target(call.syntax().range());
+        let (offset, text) = match type_param.type_bound_list() {
+            Some(bounds) => (bounds.syntax().range().end(), format!(" + {}", trait_name)),
+            None => {
+                (type_param.name().unwrap().syntax().range().end(), format!(": {}", trait_name))
+            }
+        };
+        edit.insert(offset, text);
+        edit.set_cursor(offset);
+    });
+
+    ctx.build()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::{check_assist, check_assist_target, check_assist_not_applicable};
+
+    #[test]
+    fn add_bound_for_method_on_param() {
+        check_assist(
+            add_bound_for_method,
+            "trait Clone { fn clone(&self) -> Self; }
+fn f<T>(t: T) { t.cl<|>one(); }",
+            "trait Clone { fn clone(&self) -> Self; }
+fn f<T<|>: Clone>(t: T) { t.clone(); }",
+        );
+    }
+
+    #[test]
+    fn add_bound_for_method_at_end_of_call_chain() {
+        check_assist(
+            add_bound_for_method,
+            "trait Clone { fn clone(&self) -> Self; }
+trait Debug {}
+struct W<T>(T);
+impl<T: Debug> W<T> {
+    fn get(&self) -> T {}
+    fn f(&self) { self.get().clo<|>ne(); }
+}",
+            "trait Clone { fn clone(&self) -> Self; }
+trait Debug {}
+struct W<T>(T);
+impl<T: Debug<|> + Clone> W<T> {
+    fn get(&self) -> T {}
+    fn f(&self) { self.get().clone(); }
+}",
+        );
+    }
+
+    #[test]
+    fn add_bound_for_method_target() {
+        check_assist_target(
+            add_bound_for_method,
+            "trait Clone { fn clone(&self) -> Self; }
+fn f<T>(t: T) { t.cl<|>one(); }",
+            "t.clone()",
+        );
+    }
+
+    #[test]
+    fn add_bound_for_method_not_applicable_if_bound_exists() {
+        check_assist_not_applicable(
+            add_bound_for_method,
+            "trait Clone { fn clone(&self) -> Self; }
+fn f<T: Clone>(t: T) { t.cl<|>one(); }",
+        );
+    }
+}
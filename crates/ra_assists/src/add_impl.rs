@@ -30,6 +30,10 @@ pub(crate) fn add_impl(mut ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
                 type_params.type_params().filter_map(|it| it.name()).map(|it| it.text());
             join(lifetime_params.chain(type_params)).surround_with("<", ">").to_buf(&mut buf);
         }
+        if let Some(where_clause) = nominal.where_clause() {
+            buf.push_str(" ");
+            where_clause.syntax().text().push_to(&mut buf);
+        }
         buf.push_str(" {\n");
         edit.set_cursor(start_offset + TextUnit::of_str(&buf));
         buf.push_str("\n}");
@@ -57,6 +61,11 @@ mod tests {
             "struct Foo<'a, T: Foo<'a>> {<|>}",
             "struct Foo<'a, T: Foo<'a>> {}\n\nimpl<'a, T: Foo<'a>> Foo<'a, T> {\n<|>\n}",
         );
+        check_assist(
+            add_impl,
+            "struct Foo<T> where T: Clone {<|>}",
+            "struct Foo<T> where T: Clone {}\n\nimpl<T> Foo<T> where T: Clone {\n<|>\n}",
+        );
     }
 
     #[test]
@@ -0,0 +1,171 @@
+use hir::{ModuleDef, PathResolution, db::HirDatabase};
+use ra_syntax::{
+    AstNode, SyntaxNode, TextRange,
+    ast::{self, DefaultTypeParamOwner, NameOwner, TypeParamsOwner},
+};
+
+use crate::{AssistCtx, Assist, AssistId};
+
+/// Replaces a use of a type alias by the aliased type, with the alias' type
+/// params replaced by the given args, e.g. with `type Pair<T> = (T, T);`,
+/// `Pair<i32>` becomes `(i32, i32)`. Params without an arg are replaced by
+/// their default. This is also what inlining a type alias usage means, so
+/// there is no separate `inline_type_alias` assist.
+pub(crate) fn expand_type_alias(mut ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let innermost = ctx.node_at_offset::<ast::PathType>()?;
+    let analyzer = hir::SourceAnalyzer::new(ctx.db, ctx.frange.file_id, innermost.syntax(), None);
+    // the cursor may be inside the args of the alias, like `Pair<i3<|>2>`
+    let (path_type, path, alias) =
+        innermost.syntax().ancestors().filter_map(ast::PathType::cast).find_map(|path_type| {
+            let path = path_type.path()?;
+            match analyzer.resolve_path(ctx.db, path)? {
+                PathResolution::Def(ModuleDef::TypeAlias(it)) => Some((path_type, path, it)),
+                _ => None,
+            }
+        })?;
+    let (_, alias_def) = alias.source(ctx.db);
+    let body = alias_def.type_ref()?;
+
+    let mut args = path
+        .segment()?
+        .type_arg_list()
+        .into_iter()
+        .flat_map(|it| it.type_args())
+        .map(|it| it.syntax().text().to_string());
+    let mut substs: Vec<(String, String)> = Vec::new();
+    for param in alias_def.type_param_list().into_iter().flat_map(|it| it.type_params()) {
+        let name = match param.name() {
+            Some(it) => it.text().to_string(),
+            None => continue,
+        };
+        // defaults may refer to the params before them, like `U` in `<T, U = T>`
+        let arg = match (args.next(), param.default_type()) {
+            (Some(arg), _) => arg,
+            (None, Some(default)) => substitute(default.syntax(), &substs),
+            // the param would be left in the expansion, unbound
+            (None, None) => return None,
+        };
+        substs.push((name, arg));
+    }
+    let expanded = substitute(body.syntax(), &substs);
+
+    ctx.add_action(AssistId("expand_type_alias"), "expand type alias", |edit| {
+        let range = path_type.syntax().range();
+        edit.target(range);
+        edit.replace(range, expanded);
+        edit.set_cursor(range.start());
+    });
+
+    ctx.build()
+}
+
+/// Returns the text of `node`, with the types that are just the name of a
+/// param replaced by the corresponding argument.
+fn substitute(node: &SyntaxNode, substs: &[(String, String)]) -> String {
+    let replacements: Vec<(TextRange, &str)> = node
+        .descendants()
+        .filter_map(ast::PathType::cast)
+        .filter_map(|it| {
+            let text = it.syntax().text().to_string();
+            let (_, arg) = substs.iter().find(|(param, _)| *param == text)?;
+            Some((it.syntax().range(), arg.as_str()))
+        })
+        .collect();
+    let start = node.range().start();
+    let mut text = node.text().to_string();
+    // replace back to front, so that the ranges of earlier replacements stay valid
+    for (range, arg) in replacements.into_iter().rev() {
+        let from = (range.start() - start).to_usize();
+        let to = (range.end() - start).to_usize();
+        text.replace_range(from..to, arg);
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::{check_assist, check_assist_target, check_assist_not_applicable};
+
+    #[test]
+    fn expand_generic_type_alias() {
+        check_assist(
+            expand_type_alias,
+            "type Pair<T> = (T, T);
+fn f(x: Pa<|>ir<i32>) {}",
+            "type Pair<T> = (T, T);
+fn f(x: <|>(i32, i32)) {}",
+        );
+    }
+
+    #[test]
+    fn expand_type_alias_with_nested_params() {
+        check_assist(
+            expand_type_alias,
+            "struct Vec<T>(T);
+type Map<K, V> = Vec<(K, V)>;
+fn f() -> Map<u8, <|>bool> {}",
+            "struct Vec<T>(T);
+type Map<K, V> = Vec<(K, V)>;
+fn f() -> <|>Vec<(u8, bool)> {}",
+        );
+    }
+
+    #[test]
+    fn expand_type_alias_with_fixed_arg() {
+        check_assist(
+            expand_type_alias,
+            "enum Result<T, E> { Ok(T), Err(E) }
+struct MyErr;
+type Result2<T> = Result<T, MyErr>;
+fn f() -> Result2<i3<|>2> {}",
+            "enum Result<T, E> { Ok(T), Err(E) }
+struct MyErr;
+type Result2<T> = Result<T, MyErr>;
+fn f() -> <|>Result<i32, MyErr> {}",
+        );
+    }
+
+    #[test]
+    fn expand_type_alias_keeps_defaulted_params() {
+        check_assist(
+            expand_type_alias,
+            "enum Result<T, E> { Ok(T), Err(E) }
+struct MyErr;
+type Result2<T, E = MyErr> = Result<T, E>;
+fn f() -> Result<|>2<i32> {}",
+            "enum Result<T, E> { Ok(T), Err(E) }
+struct MyErr;
+type Result2<T, E = MyErr> = Result<T, E>;
+fn f() -> <|>Result<i32, MyErr> {}",
+        );
+    }
+
+    #[test]
+    fn expand_type_alias_target() {
+        check_assist_target(
+            expand_type_alias,
+            "type Pair<T> = (T, T);
+fn f(x: Pair<i3<|>2>) {}",
+            "Pair<i32>",
+        );
+    }
+
+    #[test]
+    fn expand_type_alias_not_applicable_with_missing_arg() {
+        check_assist_not_applicable(
+            expand_type_alias,
+            "type Pair<T> = (T, T);
+fn f(x: Pa<|>ir) {}",
+        );
+    }
+
+    #[test]
+    fn expand_type_alias_not_applicable_to_struct() {
+        check_assist_not_applicable(
+            expand_type_alias,
+            "struct Pair<T>(T, T);
+fn f(x: Pa<|>ir<i32>) {}",
+        );
+    }
+}
@@ -0,0 +1,470 @@
+use hir::{Either, HirDisplay, Mutability, Ty, TypeCtor, db::HirDatabase};
+use ra_syntax::{
+    AstNode, SyntaxNode, TextRange,
+    SyntaxKind::{
+        BREAK_EXPR, CONTINUE_EXPR, FOR_EXPR, LAMBDA_EXPR, LIFETIME, LOOP_EXPR, RETURN_EXPR,
+        TRY_EXPR, WHILE_EXPR,
+    },
+    ast::{self, NameOwner, TypeParamsOwner},
+};
+
+use crate::{AssistCtx, Assist, AssistId};
+
+/// A local defined outside of the extracted block and used in it.
+struct Param {
+    name: String,
+    ty: Ty,
+    /// Whether the local is passed by reference, because it is used again
+    /// after the block and can't be copied.
+    by_ref: bool,
+    /// The range of the pattern that defines the local.
+    pat_range: TextRange,
+}
+
+/// Extracts the selected block into a new function `extracted` after the
+/// enclosing function, with the locals used by the block as its params. The
+/// generic params of the enclosing function and impl that the block refers
+/// to, either directly or through the types of those locals, are declared by
+/// the new function as well, together with their bounds.
+pub(crate) fn extract_function(mut ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    if ctx.frange.range.is_empty() {
+        return None;
+    }
+    let block = ctx.covering_element().ancestors().find_map(ast::BlockExpr::cast)?;
+    if block.syntax().range() != ctx.frange.range {
+        return None;
+    }
+    if has_escaping_control_flow(block.syntax()) {
+        return None;
+    }
+    // `Self` can't be used in the new function, which is outside of the impl
+    if block
+        .syntax()
+        .descendants()
+        .filter_map(ast::NameRef::cast)
+        .any(|it| it.text().as_str() == "Self")
+    {
+        return None;
+    }
+    let fn_def = block.syntax().ancestors().find_map(ast::FnDef::cast)?;
+    let impl_block =
+        fn_def.syntax().parent().and_then(|it| it.parent()).and_then(ast::ImplBlock::cast);
+    let analyzer = hir::SourceAnalyzer::new(ctx.db, ctx.frange.file_id, block.syntax(), None);
+    let local_range = |name_ref: &ast::NameRef| -> Option<TextRange> {
+        match analyzer.resolve_local_name(name_ref)?.ptr() {
+            Either::A(pat) => Some(pat.syntax_node_ptr().range()),
+            Either::B(self_param) => Some(self_param.syntax_node_ptr().range()),
+        }
+    };
+
+    // the locals defined outside of the block become params, in the order of
+    // their first use
+    let mut params: Vec<Param> = Vec::new();
+    for path_expr in block.syntax().descendants().filter_map(ast::PathExpr::cast) {
+        let name_ref = match local_name_ref(path_expr) {
+            Some(it) => it,
+            None => continue,
+        };
+        let entry = match analyzer.resolve_local_name(name_ref) {
+            Some(it) => it,
+            None => continue,
+        };
+        let pat_range = match entry.ptr() {
+            Either::A(pat) => pat.syntax_node_ptr().range(),
+            // methods can't be extracted from yet
+            Either::B(_) => return None,
+        };
+        let name = entry.name().to_string();
+        if pat_range.is_subrange(&block.syntax().range()) || params.iter().any(|p| p.name == name) {
+            continue;
+        }
+        let ty = analyzer.type_of(ctx.db, ast::Expr::cast(path_expr.syntax())?)?;
+        if contains_unknown(&ty) {
+            return None;
+        }
+        let end = block.syntax().range().end();
+        let used_after = fn_def
+            .syntax()
+            .descendants()
+            .filter_map(ast::NameRef::cast)
+            .filter(|it| it.syntax().range().start() >= end)
+            .any(|it| local_range(it) == Some(pat_range));
+        params.push(Param { name, by_ref: used_after && !is_copy(&ty), ty, pat_range });
+    }
+    let ret_ty = analyzer.type_of(ctx.db, ast::Expr::cast(block.syntax())?)?;
+    if contains_unknown(&ret_ty) {
+        return None;
+    }
+
+    let type_param_lists: Vec<&ast::TypeParamList> = impl_block
+        .and_then(|it| it.type_param_list())
+        .into_iter()
+        .chain(fn_def.type_param_list())
+        .collect();
+    let where_preds: Vec<&ast::WherePred> = impl_block
+        .and_then(|it| it.where_clause())
+        .into_iter()
+        .chain(fn_def.where_clause())
+        .flat_map(|it| it.predicates())
+        .collect();
+    let type_params: Vec<(String, &ast::TypeParam)> = type_param_lists
+        .iter()
+        .flat_map(|it| it.type_params())
+        .filter_map(|it| Some((it.name()?.text().to_string(), it)))
+        .collect();
+
+    // the params used by the block, and then the params used by the bounds of
+    // those, until no new ones are found
+    let block_names = mentioned_names(block.syntax());
+    let mut used_params: Vec<&str> = type_params
+        .iter()
+        .map(|(name, _)| name.as_str())
+        .filter(|name| {
+            params.iter().any(|p| mentions_param(&p.ty, name))
+                || mentions_param(&ret_ty, name)
+                || block_names.iter().any(|it| it.as_str() == *name)
+        })
+        .collect();
+    let mut used_preds: Vec<&ast::WherePred> = Vec::new();
+    loop {
+        let mut mentioned = Vec::new();
+        for (name, param) in type_params.iter() {
+            if used_params.contains(&name.as_str()) {
+                mentioned.extend(mentioned_names(param.syntax()));
+            }
+        }
+        for pred in where_preds.iter() {
+            let subject_names = pred.type_ref().map(|it| mentioned_names(it.syntax()));
+            if subject_names
+                .map_or(false, |it| it.iter().any(|n| used_params.contains(&n.as_str())))
+                && !used_preds.contains(pred)
+            {
+                used_preds.push(pred);
+                mentioned.extend(mentioned_names(pred.syntax()));
+            }
+        }
+        let new_params: Vec<&str> = type_params
+            .iter()
+            .map(|(name, _)| name.as_str())
+            .filter(|name| {
+                !used_params.contains(name) && mentioned.iter().any(|it| it.as_str() == *name)
+            })
+            .collect();
+        if new_params.is_empty() {
+            break;
+        }
+        used_params.extend(new_params);
+    }
+
+    let mut used_lifetimes = lifetimes_in(block.syntax());
+    for (name, param) in type_params.iter() {
+        if used_params.contains(&name.as_str()) {
+            used_lifetimes.extend(lifetimes_in(param.syntax()));
+        }
+    }
+    for pred in used_preds.iter() {
+        used_lifetimes.extend(lifetimes_in(pred.syntax()));
+    }
+    let generic_params: Vec<String> = type_param_lists
+        .iter()
+        .flat_map(|it| it.lifetime_params())
+        .filter(|it| {
+            it.lifetime_token()
+                .map_or(false, |it| used_lifetimes.iter().any(|l| l.as_str() == it.text().as_str()))
+        })
+        .map(|it| it.syntax().text().to_string())
+        .chain(
+            type_params
+                .iter()
+                .filter(|(name, _)| used_params.contains(&name.as_str()))
+                .map(|(_, param)| param.syntax().text().to_string()),
+        )
+        .collect();
+
+    let mut buf = String::from("\n\nfn extracted");
+    if !generic_params.is_empty() {
+        buf.push_str(&format!("<{}>", generic_params.join(", ")));
+    }
+    let param_list: Vec<String> = params
+        .iter()
+        .map(|p| format!("{}: {}{}", p.name, if p.by_ref { "&" } else { "" }, p.ty.display(ctx.db)))
+        .collect();
+    buf.push_str(&format!("({})", param_list.join(", ")));
+    if ret_ty != Ty::unit() {
+        buf.push_str(&format!(" -> {}", ret_ty.display(ctx.db)));
+    }
+    if !used_preds.is_empty() {
+        let preds: Vec<String> =
+            used_preds.iter().map(|it| it.syntax().text().to_string()).collect();
+        buf.push_str(&format!(" where {}", preds.join(", ")));
+    }
+    buf.push_str(" ");
+    // the locals passed by reference have to be dereferenced where they are
+    // used by value
+    let derefs: Vec<TextRange> = block
+        .syntax()
+        .descendants()
+        .filter_map(ast::PathExpr::cast)
+        .filter(|it| !is_autoderefed(it))
+        .filter(|it| {
+            let pat_range = local_name_ref(it).and_then(|it| local_range(it));
+            params.iter().any(|p| p.by_ref && Some(p.pat_range) == pat_range)
+        })
+        .map(|it| it.syntax().range())
+        .collect();
+    let block_start = block.syntax().range().start();
+    let mut body = block.syntax().text().to_string();
+    // insert back to front, so that the offsets of earlier derefs stay valid
+    for range in derefs.into_iter().rev() {
+        body.insert((range.start() - block_start).to_usize(), '*');
+    }
+    buf.push_str(&body);
+    let args: Vec<String> =
+        params.iter().map(|p| format!("{}{}", if p.by_ref { "&" } else { "" }, p.name)).collect();
+    let call = format!("extracted({})", args.join(", "));
+
+    ctx.add_action(AssistId("extract_function"), "extract into function", |edit| {
+        edit.target(block.syntax().range());
+        // for methods, the new function goes after the impl, not into it
+        let end = match &impl_block {
+            Some(it) => it.syntax().range().end(),
+            None => fn_def.syntax().range().end(),
+        };
+        edit.insert(end, buf);
+        edit.replace(block.syntax().range(), call);
+        edit.set_cursor(block.syntax().range().start());
+    });
+
+    ctx.build()
+}
+
+/// The name of `path_expr` if it is a single identifier, like `x`.
+fn local_name_ref(path_expr: &ast::PathExpr) -> Option<&ast::NameRef> {
+    path_expr.path().filter(|it| it.qualifier().is_none())?.segment()?.name_ref()
+}
+
+/// Whether `path_expr` is the receiver of a method call or of a field access,
+/// which dereference it automatically.
+fn is_autoderefed(path_expr: &ast::PathExpr) -> bool {
+    let parent = match path_expr.syntax().parent() {
+        Some(it) => it,
+        None => return false,
+    };
+    let receiver = if let Some(call) = ast::MethodCallExpr::cast(parent) {
+        call.expr()
+    } else if let Some(field) = ast::FieldExpr::cast(parent) {
+        field.expr()
+    } else {
+        None
+    };
+    receiver.map_or(false, |it| it.syntax() == path_expr.syntax())
+}
+
+/// Whether `block` contains a `return`, `?`, `break` or `continue` that jumps
+/// out of it, which can't be moved into another function. Jumps in closures,
+/// and `break` and `continue` in loops inside of the block are fine.
+fn has_escaping_control_flow(block: &SyntaxNode) -> bool {
+    block.descendants().any(|node| {
+        let is_loop_jump = match node.kind() {
+            RETURN_EXPR | TRY_EXPR => false,
+            BREAK_EXPR | CONTINUE_EXPR => true,
+            _ => return false,
+        };
+        !node.ancestors().skip(1).take_while(|it| it.range() != block.range()).any(|it| {
+            match it.kind() {
+                LAMBDA_EXPR => true,
+                LOOP_EXPR | WHILE_EXPR | FOR_EXPR => is_loop_jump,
+                _ => false,
+            }
+        })
+    })
+}
+
+/// The texts of the name refs in `node`, like `T` and `Clone` for `T: Clone`.
+fn mentioned_names(node: &SyntaxNode) -> Vec<String> {
+    node.descendants().filter_map(ast::NameRef::cast).map(|it| it.text().to_string()).collect()
+}
+
+/// The texts of the lifetimes in `node`, like `'a` for `&'a T`.
+fn lifetimes_in(node: &SyntaxNode) -> Vec<String> {
+    node.descendants_with_tokens()
+        .filter_map(|it| it.as_token())
+        .filter(|it| it.kind() == LIFETIME)
+        .map(|it| it.text().to_string())
+        .collect()
+}
+
+/// Whether `ty` mentions the type param `name`, like `Vec<T>` mentions `T`.
+fn mentions_param(ty: &Ty, name: &str) -> bool {
+    let mut mentioned = false;
+    ty.walk(&mut |ty| match ty {
+        Ty::Param { name: param_name, .. } if param_name.to_string() == name => mentioned = true,
+        _ => {}
+    });
+    mentioned
+}
+
+/// Whether `ty` is or contains a type that couldn't be inferred, and so can't
+/// be written down in the signature.
+fn contains_unknown(ty: &Ty) -> bool {
+    let mut unknown = false;
+    ty.walk(&mut |ty| {
+        if let Ty::Unknown = ty {
+            unknown = true;
+        }
+    });
+    unknown
+}
+
+/// Whether `ty` is known to be `Copy`, so that passing it by value doesn't
+/// move it out of the caller. Types that might not be `Copy`, like ADTs and
+/// type params, aren't.
+fn is_copy(ty: &Ty) -> bool {
+    let a_ty = match ty {
+        Ty::Apply(it) => it,
+        _ => return false,
+    };
+    match a_ty.ctor {
+        TypeCtor::Bool
+        | TypeCtor::Char
+        | TypeCtor::Int(_)
+        | TypeCtor::Float(_)
+        | TypeCtor::Never
+        | TypeCtor::RawPtr(_)
+        | TypeCtor::FnDef(_)
+        | TypeCtor::FnPtr { .. }
+        | TypeCtor::Ref(Mutability::Shared) => true,
+        TypeCtor::Tuple { .. } | TypeCtor::Array => a_ty.parameters.iter().all(is_copy),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::{
+        check_assist_range, check_assist_range_target, check_assist_range_not_applicable,
+    };
+
+    #[test]
+    fn extract_function_with_generic_param() {
+        check_assist_range(
+            extract_function,
+            "
+trait Clone {}
+fn foo<T: Clone, U>(t: T, u: U) -> (T, u32) {
+    let x = <|>{ (t, 1u32) }<|>;
+    x
+}",
+            "
+trait Clone {}
+fn foo<T: Clone, U>(t: T, u: U) -> (T, u32) {
+    let x = <|>extracted(t);
+    x
+}
+
+fn extracted<T: Clone>(t: T) -> (T, u32) { (t, 1u32) }",
+        );
+    }
+
+    #[test]
+    fn extract_function_with_param_only_used_in_type() {
+        check_assist_range(
+            extract_function,
+            "
+fn foo<T>() {
+    <|>{ let x: Option<T> = None; }<|>
+}",
+            "
+fn foo<T>() {
+    <|>extracted()
+}
+
+fn extracted<T>() { let x: Option<T> = None; }",
+        );
+    }
+
+    #[test]
+    fn extract_function_in_method_declares_impl_params() {
+        check_assist_range(
+            extract_function,
+            "
+trait Clone {}
+trait Debug {}
+struct S<T>(T);
+impl<T> S<T> where T: Debug {
+    fn foo<U: Clone>(&self, t: T, u: U) -> (T, U) {
+        <|>{ (t, u) }<|>
+    }
+}",
+            "
+trait Clone {}
+trait Debug {}
+struct S<T>(T);
+impl<T> S<T> where T: Debug {
+    fn foo<U: Clone>(&self, t: T, u: U) -> (T, U) {
+        <|>extracted(t, u)
+    }
+}
+
+fn extracted<T, U: Clone>(t: T, u: U) -> (T, U) where T: Debug { (t, u) }",
+        );
+    }
+
+    #[test]
+    fn extract_function_passes_locals_used_later_by_reference() {
+        check_assist_range(
+            extract_function,
+            "
+struct Vec;
+impl Vec {
+    fn len(&self) -> u32 { 0 }
+}
+fn take(v: Vec) {}
+fn foo(v: Vec, n: u32) {
+    let m = <|>{ let w = &v; w.len() + v.len() + n }<|>;
+    take(v);
+    n;
+}",
+            "
+struct Vec;
+impl Vec {
+    fn len(&self) -> u32 { 0 }
+}
+fn take(v: Vec) {}
+fn foo(v: Vec, n: u32) {
+    let m = <|>extracted(&v, n);
+    take(v);
+    n;
+}
+
+fn extracted(v: &Vec, n: u32) -> u32 { let w = &*v; w.len() + v.len() + n }",
+        );
+    }
+
+    #[test]
+    fn extract_function_target() {
+        check_assist_range_target(extract_function, "fn foo() { let x = <|>{ 1 }<|>; }", "{ 1 }");
+    }
+
+    #[test]
+    fn extract_function_not_applicable_to_partial_block() {
+        check_assist_range_not_applicable(extract_function, "fn foo() { let x = { <|>1<|> }; }");
+    }
+
+    #[test]
+    fn extract_function_not_applicable_to_block_with_return() {
+        check_assist_range_not_applicable(
+            extract_function,
+            "fn foo() -> u32 { let x = <|>{ return 1; }<|>; 2 }",
+        );
+    }
+
+    #[test]
+    fn extract_function_not_applicable_with_unknown_types() {
+        check_assist_range_not_applicable(
+            extract_function,
+            "fn foo() { let x = unknown(); let y = <|>{ x }<|>; }",
+        );
+    }
+}
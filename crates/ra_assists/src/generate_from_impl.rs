@@ -0,0 +1,172 @@
+use join_to_string::join;
+use hir::db::HirDatabase;
+use ra_syntax::{
+    AstNode, TextUnit,
+    ast::{self, NameOwner, TypeAscriptionOwner, TypeBoundsOwner, TypeParamsOwner},
+};
+
+use crate::{AssistCtx, Assist, AssistId};
+
+/// Adds a `From` impl for a struct with a single field, converting from the
+/// type of that field, e.g. `impl<T> From<T> for Wrapper<T>` for
+/// `struct Wrapper<T>(T);`.
+pub(crate) fn generate_from_impl(mut ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let struct_def = ctx.node_at_offset::<ast::StructDef>()?;
+    let name = struct_def.name()?;
+    let (field_name, field_type) = match struct_def.kind() {
+        ast::StructKind::Tuple(fields) => {
+            let mut fields = fields.fields();
+            let field = fields.next()?;
+            if fields.next().is_some() {
+                return None;
+            }
+            (None, field.type_ref()?)
+        }
+        ast::StructKind::Named(fields) => {
+            let mut fields = fields.fields();
+            let field = fields.next()?;
+            if fields.next().is_some() {
+                return None;
+            }
+            (Some(field.name()?.text().to_string()), field.type_ref()?)
+        }
+        ast::StructKind::Unit => return None,
+    };
+    let field_type = field_type.syntax().text().to_string();
+    // the param is named after the field, so that it can be used in the
+    // shorthand `Meters { value }`
+    let param_name = field_name.clone().unwrap_or_else(|| "value".to_string());
+
+    ctx.add_action(AssistId("generate_from_impl"), "generate `From` impl", |edit| {
+        edit.target(struct_def.syntax().range());
+        let type_params = struct_def.type_param_list();
+        let start_offset = struct_def.syntax().range().end();
+        let mut buf = String::new();
+        buf.push_str("\n\nimpl");
+        if let Some(type_params) = type_params {
+            // the params are repeated without their defaults, which aren't
+            // allowed on impls
+            let lifetime_params =
+                type_params.lifetime_params().map(|it| it.syntax().text().to_string());
+            let type_params = type_params.type_params().filter_map(|it| {
+                let name = it.name()?.text().to_string();
+                match it.type_bound_list() {
+                    Some(bounds) => Some(format!("{}: {}", name, bounds.syntax().text())),
+                    None => Some(name),
+                }
+            });
+            join(lifetime_params.chain(type_params)).surround_with("<", ">").to_buf(&mut buf);
+        }
+        buf.push_str(&format!(" From<{}> for {}", field_type, name.text()));
+        if let Some(type_params) = type_params {
+            let lifetime_params = type_params
+                .lifetime_params()
+                .filter_map(|it| it.lifetime_token())
+                .map(|it| it.text());
+            let type_params =
+                type_params.type_params().filter_map(|it| it.name()).map(|it| it.text());
+            join(lifetime_params.chain(type_params)).surround_with("<", ">").to_buf(&mut buf);
+        }
+        if let Some(where_clause) = struct_def.where_clause() {
+            buf.push_str(" ");
+            where_clause.syntax().text().push_to(&mut buf);
+        }
+        buf.push_str(&format!(
+            " {{\n    fn from({}: {}) -> Self {{\n        ",
+            param_name, field_type
+        ));
+        edit.set_cursor(start_offset + TextUnit::of_str(&buf));
+        match field_name {
+            Some(_) => buf.push_str(&format!("{} {{ {} }}", name.text(), param_name)),
+            None => buf.push_str(&format!("{}({})", name.text(), param_name)),
+        }
+        buf.push_str("\n    }\n}");
+        edit.insert(start_offset, buf);
+    });
+
+    ctx.build()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::{check_assist, check_assist_target, check_assist_not_applicable};
+
+    #[test]
+    fn generate_from_impl_for_generic_newtype() {
+        check_assist(
+            generate_from_impl,
+            "struct Wrapper<T>(<|>T);",
+            "struct Wrapper<T>(T);
+
+impl<T> From<T> for Wrapper<T> {
+    fn from(value: T) -> Self {
+        <|>Wrapper(value)
+    }
+}",
+        );
+    }
+
+    #[test]
+    fn generate_from_impl_drops_param_defaults() {
+        check_assist(
+            generate_from_impl,
+            "trait Clone {}
+struct Wrapper<'a, T: Clone = u8>(<|>&'a T);",
+            "trait Clone {}
+struct Wrapper<'a, T: Clone = u8>(&'a T);
+
+impl<'a, T: Clone> From<&'a T> for Wrapper<'a, T> {
+    fn from(value: &'a T) -> Self {
+        <|>Wrapper(value)
+    }
+}",
+        );
+    }
+
+    #[test]
+    fn generate_from_impl_names_param_independently_of_type() {
+        check_assist(
+            generate_from_impl,
+            "struct Type;
+struct Wrapper(<|>Type);",
+            "struct Type;
+struct Wrapper(Type);
+
+impl From<Type> for Wrapper {
+    fn from(value: Type) -> Self {
+        <|>Wrapper(value)
+    }
+}",
+        );
+    }
+
+    #[test]
+    fn generate_from_impl_for_record_struct() {
+        check_assist(
+            generate_from_impl,
+            "struct Meters<|> { value: u32 }",
+            "struct Meters { value: u32 }
+
+impl From<u32> for Meters {
+    fn from(value: u32) -> Self {
+        <|>Meters { value }
+    }
+}",
+        );
+    }
+
+    #[test]
+    fn generate_from_impl_target() {
+        check_assist_target(
+            generate_from_impl,
+            "struct Wrapper<T>(<|>T);",
+            "struct Wrapper<T>(T);",
+        );
+    }
+
+    #[test]
+    fn generate_from_impl_not_applicable_to_struct_with_several_fields() {
+        check_assist_not_applicable(generate_from_impl, "struct Pair<T>(<|>T, T);");
+    }
+}
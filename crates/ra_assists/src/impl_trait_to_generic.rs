@@ -0,0 +1,141 @@
+use hir::db::HirDatabase;
+use ra_syntax::{
+    AstNode, TextUnit,
+    SyntaxKind::R_ANGLE,
+    ast::{self, NameOwner, TypeBoundsOwner, TypeParamsOwner},
+};
+
+use crate::{AssistCtx, Assist, AssistId, introduce_type_param::free_type_param_names};
+
+/// Replaces `impl Trait` argument types by fresh type params with the same
+/// bounds, e.g. `fn f(x: impl Display)` becomes `fn f<T: Display>(x: T)`.
+/// All `impl Trait` args of the function are converted, each into a param of
+/// its own. Nested ones, like in `impl Iterator<Item = impl Debug>`, are
+/// converted innermost first, so that the outer bound refers to the new param
+/// of the inner one.
+pub(crate) fn impl_trait_to_generic(mut ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let impl_trait = ctx.node_at_offset::<ast::ImplTraitType>()?;
+    let param_list = impl_trait.syntax().ancestors().find_map(ast::ParamList::cast)?;
+    let fn_def = param_list.syntax().parent().and_then(ast::FnDef::cast)?;
+    let name = fn_def.name()?;
+
+    let mut free_names = free_type_param_names(fn_def);
+    let mut new_params = Vec::new();
+    let mut replacements = Vec::new();
+    for impl_trait in param_list.syntax().descendants().filter_map(ast::ImplTraitType::cast) {
+        if enclosing_impl_trait(impl_trait).is_none() {
+            let param_name = convert(impl_trait, &mut free_names, &mut new_params)?;
+            replacements.push((impl_trait, param_name));
+        }
+    }
+
+    ctx.add_action(AssistId("impl_trait_to_generic"), "convert impl trait to generic", |edit| {
+        edit.target(impl_trait.syntax().range());
+        let (offset, text) = match fn_def.type_param_list() {
+            Some(list) => {
+                let r_angle = list
+                    .syntax()
+                    .children_with_tokens()
+                    .find(|it| it.kind() == R_ANGLE)
+                    .map(|it| it.range().start())
+                    .unwrap_or_else(|| list.syntax().range().end());
+                (r_angle, format!(", {}", new_params.join(", ")))
+            }
+            None => (name.syntax().range().end(), format!("<{}>", new_params.join(", "))),
+        };
+        edit.insert(offset, text.clone());
+        for (impl_trait, param_name) in replacements.iter() {
+            edit.replace(impl_trait.syntax().range(), *param_name);
+        }
+        let first_param = if fn_def.type_param_list().is_some() { ", " } else { "<" };
+        edit.set_cursor(offset + TextUnit::of_str(first_param));
+    });
+
+    ctx.build()
+}
+
+/// Allocates a param for `impl_trait` and returns its name; the params for
+/// the `impl Trait`s nested in its bounds are allocated after it, and are
+/// referred to by name in its bounds.
+fn convert(
+    impl_trait: &ast::ImplTraitType,
+    free_names: &mut impl Iterator<Item = &'static str>,
+    new_params: &mut Vec<String>,
+) -> Option<&'static str> {
+    let param_name = free_names.next()?;
+    let idx = new_params.len();
+    new_params.push(String::new());
+    let bound_list = impl_trait.type_bound_list()?;
+    let mut nested = Vec::new();
+    for it in bound_list.syntax().descendants().filter_map(ast::ImplTraitType::cast) {
+        if enclosing_impl_trait(it) == Some(impl_trait) {
+            nested.push((it.syntax().range(), convert(it, free_names, new_params)?));
+        }
+    }
+    let start = bound_list.syntax().range().start();
+    let mut bounds = bound_list.syntax().text().to_string();
+    // replace back to front, so that the ranges of earlier replacements stay valid
+    for (range, name) in nested.into_iter().rev() {
+        let from = (range.start() - start).to_usize();
+        let to = (range.end() - start).to_usize();
+        bounds.replace_range(from..to, name);
+    }
+    new_params[idx] = format!("{}: {}", param_name, bounds);
+    Some(param_name)
+}
+
+fn enclosing_impl_trait(impl_trait: &ast::ImplTraitType) -> Option<&ast::ImplTraitType> {
+    impl_trait.syntax().ancestors().skip(1).find_map(ast::ImplTraitType::cast)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::{check_assist, check_assist_target, check_assist_not_applicable};
+
+    #[test]
+    fn impl_trait_to_generic_single_arg() {
+        check_assist(
+            impl_trait_to_generic,
+            "fn f(x: impl Dis<|>play) {}",
+            "fn f<<|>T: Display>(x: T) {}",
+        );
+    }
+
+    #[test]
+    fn impl_trait_to_generic_multiple_args() {
+        check_assist(
+            impl_trait_to_generic,
+            "fn f<T>(t: T, x: impl Dis<|>play + Clone, y: Vec<impl Debug>) {}",
+            "fn f<T, <|>U: Display + Clone, V: Debug>(t: T, x: U, y: Vec<V>) {}",
+        );
+    }
+
+    #[test]
+    fn impl_trait_to_generic_nested() {
+        check_assist(
+            impl_trait_to_generic,
+            "fn f(x: impl Iterator<Item = impl Dis<|>play>, y: impl Clone) {}",
+            "fn f<<|>T: Iterator<Item = U>, U: Display, V: Clone>(x: T, y: V) {}",
+        );
+    }
+
+    #[test]
+    fn impl_trait_to_generic_skips_trait_params() {
+        check_assist(
+            impl_trait_to_generic,
+            "trait Tr<T> { fn f(x: impl Dis<|>play, y: T); }",
+            "trait Tr<T> { fn f<<|>U: Display>(x: U, y: T); }",
+        );
+    }
+
+    #[test]
+    fn impl_trait_to_generic_target() {
+        check_assist_target(impl_trait_to_generic, "fn f(x: impl Dis<|>play) {}", "impl Display");
+    }
+
+    #[test]
+    fn impl_trait_to_generic_not_applicable_in_return_type() {
+        check_assist_not_applicable(impl_trait_to_generic, "fn f() -> impl Dis<|>play {}");
+    }
+}
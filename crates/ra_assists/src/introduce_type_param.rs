@@ -0,0 +1,150 @@
+use hir::db::HirDatabase;
+use ra_syntax::{
+    AstNode, TextUnit,
+    SyntaxKind::{ITEM_LIST, R_ANGLE},
+    ast::{self, NameOwner, TypeParamsOwner},
+};
+
+use crate::{AssistCtx, Assist, AssistId};
+
+const CANDIDATE_NAMES: &[&str] = &["T", "U", "V", "W"];
+
+/// Replaces a concrete type in a function signature with a fresh type
+/// parameter, e.g. `fn f(x: i32) -> i32` becomes `fn f<T>(x: T) -> T`.
+pub(crate) fn introduce_type_param(mut ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let path_type = ctx.node_at_offset::<ast::PathType>()?;
+    let fn_def = path_type.syntax().ancestors().find_map(ast::FnDef::cast)?;
+    let name = fn_def.name()?;
+    let param_list = fn_def.param_list();
+    let ret_type = fn_def.ret_type();
+
+    // Only types in the signature are candidates, not those in the body
+    let in_signature = param_list.map_or(false, |it| is_within(it.syntax(), path_type.syntax()))
+        || ret_type.map_or(false, |it| is_within(it.syntax(), path_type.syntax()));
+    if !in_signature {
+        return None;
+    }
+
+    let type_params: Vec<String> = fn_def
+        .type_param_list()
+        .into_iter()
+        .flat_map(|it| it.type_params())
+        .filter_map(|it| it.name())
+        .map(|it| it.text().to_string())
+        .collect();
+    let target_text = path_type.syntax().text().to_string();
+    // The type already is a type parameter
+    if type_params.contains(&target_text) {
+        return None;
+    }
+    let new_name = free_type_param_names(fn_def).next()?;
+
+    let occurrences: Vec<&ast::PathType> = param_list
+        .into_iter()
+        .map(|it| it.syntax())
+        .chain(ret_type.into_iter().map(|it| it.syntax()))
+        .flat_map(|it| it.descendants())
+        .filter_map(ast::PathType::cast)
+        .filter(|it| it.syntax().text() == target_text.as_str())
+        .collect();
+
+    ctx.add_action(AssistId("introduce_type_param"), "introduce type parameter", |edit| {
+        edit.target(path_type.syntax().range());
+        let (offset, text) = match fn_def.type_param_list() {
+            Some(list) => {
+                let r_angle = list
+                    .syntax()
+                    .children_with_tokens()
+                    .find(|it| it.kind() == R_ANGLE)
+                    .map(|it| it.range().start())
+                    .unwrap_or_else(|| list.syntax().range().end());
+                (r_angle, format!(", {}", new_name))
+            }
+            None => (name.syntax().range().end(), format!("<{}>", new_name)),
+        };
+        edit.insert(offset, text.clone());
+        for occurrence in occurrences.iter() {
+            edit.replace(occurrence.syntax().range(), new_name);
+        }
+        edit.set_cursor(offset + TextUnit::of_str(&text) - TextUnit::of_str(new_name));
+    });
+
+    ctx.build()
+}
+
+/// Returns the candidate names for a new type param of `fn_def` that don't
+/// shadow a type param in scope, i.e. one of its own or of the enclosing impl
+/// or trait.
+pub(crate) fn free_type_param_names(fn_def: &ast::FnDef) -> impl Iterator<Item = &'static str> {
+    let enclosing_params = fn_def
+        .syntax()
+        .parent()
+        .filter(|it| it.kind() == ITEM_LIST)
+        .and_then(|it| it.parent())
+        .and_then(|it| {
+            ast::ImplBlock::cast(it)
+                .and_then(|it| it.type_param_list())
+                .or_else(|| ast::TraitDef::cast(it).and_then(|it| it.type_param_list()))
+        });
+    let taken: Vec<String> = fn_def
+        .type_param_list()
+        .into_iter()
+        .chain(enclosing_params)
+        .flat_map(|it| it.type_params())
+        .filter_map(|it| it.name())
+        .map(|it| it.text().to_string())
+        .collect();
+    CANDIDATE_NAMES.iter().cloned().filter(move |it| !taken.iter().any(|p| p == it))
+}
+
+fn is_within(container: &ra_syntax::SyntaxNode, node: &ra_syntax::SyntaxNode) -> bool {
+    node.ancestors().any(|it| it == container)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::{check_assist, check_assist_target, check_assist_not_applicable};
+
+    #[test]
+    fn introduce_type_param_replaces_all_occurrences() {
+        check_assist(
+            introduce_type_param,
+            "fn f(x: i3<|>2) -> i32 { x }",
+            "fn f<<|>T>(x: T) -> T { x }",
+        );
+    }
+
+    #[test]
+    fn introduce_type_param_appends_to_existing_params() {
+        check_assist(
+            introduce_type_param,
+            "fn f<T>(x: T, y: Vec<u<|>8>) -> u8 {}",
+            "fn f<T, <|>U>(x: T, y: Vec<U>) -> U {}",
+        );
+    }
+
+    #[test]
+    fn introduce_type_param_skips_impl_params() {
+        check_assist(
+            introduce_type_param,
+            "impl<T> S<T> { fn f(&self, x: i3<|>2) {} }",
+            "impl<T> S<T> { fn f<<|>U>(&self, x: U) {} }",
+        );
+    }
+
+    #[test]
+    fn introduce_type_param_target() {
+        check_assist_target(introduce_type_param, "fn f(x: i3<|>2) {}", "i32");
+    }
+
+    #[test]
+    fn introduce_type_param_not_applicable_in_body() {
+        check_assist_not_applicable(introduce_type_param, "fn f() { let x: i3<|>2 = 1; }");
+    }
+
+    #[test]
+    fn introduce_type_param_not_applicable_to_type_param() {
+        check_assist_not_applicable(introduce_type_param, "fn f<T>(x: <|>T) {}");
+    }
+}
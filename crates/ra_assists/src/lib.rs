@@ -89,14 +89,22 @@ where
 mod add_derive;
 mod add_explicit_type;
 mod add_impl;
+mod generate_from_impl;
 mod flip_comma;
 mod flip_binexpr;
 mod change_visibility;
 mod fill_match_arms;
 mod introduce_variable;
+mod extract_function;
+mod introduce_type_param;
 mod inline_local_variable;
 mod replace_if_let_with_match;
 mod split_import;
+mod split_bounds;
+mod expand_type_alias;
+mod impl_trait_to_generic;
+mod add_bound_for_method;
+mod sort_generic_params;
 mod remove_dbg;
 pub mod auto_import;
 mod add_missing_impl_members;
@@ -107,13 +115,21 @@ fn all_assists<DB: HirDatabase>() -> &'static [fn(AssistCtx<DB>) -> Option<Assis
         add_derive::add_derive,
         add_explicit_type::add_explicit_type,
         add_impl::add_impl,
+        generate_from_impl::generate_from_impl,
         change_visibility::change_visibility,
         fill_match_arms::fill_match_arms,
         flip_comma::flip_comma,
         flip_binexpr::flip_binexpr,
         introduce_variable::introduce_variable,
+        extract_function::extract_function,
+        introduce_type_param::introduce_type_param,
         replace_if_let_with_match::replace_if_let_with_match,
         split_import::split_import,
+        split_bounds::split_bounds,
+        expand_type_alias::expand_type_alias,
+        impl_trait_to_generic::impl_trait_to_generic,
+        add_bound_for_method::add_bound_for_method,
+        sort_generic_params::sort_generic_params,
         remove_dbg::remove_dbg,
         auto_import::auto_import,
         add_missing_impl_members::add_missing_impl_members,
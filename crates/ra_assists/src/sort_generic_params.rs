@@ -0,0 +1,75 @@
+use hir::db::HirDatabase;
+use ra_syntax::{
+    AstNode, SyntaxNode, TextRange,
+    SyntaxKind::{LIFETIME_PARAM, TYPE_PARAM},
+    ast,
+};
+
+use crate::{AssistCtx, Assist, AssistId};
+
+/// Moves the lifetime params of a generic param list in front of the type
+/// params, e.g. `<T, 'a>` becomes `<'a, T>`. The params are kept otherwise
+/// unchanged.
+pub(crate) fn sort_generic_params(mut ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let list = ctx.node_at_offset::<ast::TypeParamList>()?;
+    // FIXME: const params go last, once they are parsed
+    let params: Vec<&SyntaxNode> = list
+        .syntax()
+        .children()
+        .filter(|it| it.kind() == LIFETIME_PARAM || it.kind() == TYPE_PARAM)
+        .collect();
+    let out_of_order =
+        params.windows(2).any(|w| w[0].kind() == TYPE_PARAM && w[1].kind() == LIFETIME_PARAM);
+    if !out_of_order {
+        return None;
+    }
+    let mut sorted = params.clone();
+    sorted.sort_by_key(|it| it.kind() != LIFETIME_PARAM);
+    let sorted: Vec<String> = sorted.iter().map(|it| it.text().to_string()).collect();
+
+    ctx.add_action(AssistId("sort_generic_params"), "sort generic params", |edit| {
+        edit.target(list.syntax().range());
+        let range = TextRange::from_to(
+            params.first().unwrap().range().start(),
+            params.last().unwrap().range().end(),
+        );
+        edit.replace(range, sorted.join(", "));
+        edit.set_cursor(range.start());
+    });
+
+    ctx.build()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::{check_assist, check_assist_target, check_assist_not_applicable};
+
+    #[test]
+    fn sort_generic_params_moves_lifetimes_first() {
+        check_assist(
+            sort_generic_params,
+            "fn f<T: Clone, 'a, <|>U, 'b: 'a>(x: &'a T, y: &'b U) {}",
+            "fn f<<|>'a, 'b: 'a, T: Clone, U>(x: &'a T, y: &'b U) {}",
+        );
+    }
+
+    #[test]
+    fn sort_generic_params_of_struct() {
+        check_assist(
+            sort_generic_params,
+            "struct S<T, <|>'a>(&'a T);",
+            "struct S<<|>'a, T>(&'a T);",
+        );
+    }
+
+    #[test]
+    fn sort_generic_params_target() {
+        check_assist_target(sort_generic_params, "fn f<T, <|>'a>() {}", "<T, 'a>");
+    }
+
+    #[test]
+    fn sort_generic_params_not_applicable_to_sorted_params() {
+        check_assist_not_applicable(sort_generic_params, "fn f<'a, T<|>, U>() {}");
+    }
+}
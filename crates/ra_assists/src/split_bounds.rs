@@ -0,0 +1,74 @@
+use hir::db::HirDatabase;
+use ra_syntax::{
+    AstNode,
+    SyntaxKind::LIFETIME,
+    ast::{self, TypeBoundsOwner},
+};
+
+use crate::{AssistCtx, Assist, AssistId};
+
+/// Splits a where predicate with several bounds into one predicate per bound,
+/// e.g. `where T: Foo + Bar` becomes `where T: Foo, T: Bar`.
+pub(crate) fn split_bounds(mut ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let pred = ctx.node_at_offset::<ast::WherePred>()?;
+    let subject = match pred.type_ref() {
+        Some(type_ref) => type_ref.syntax().text().to_string(),
+        None => pred
+            .syntax()
+            .children_with_tokens()
+            .find(|it| it.kind() == LIFETIME)?
+            .as_token()?
+            .text()
+            .to_string(),
+    };
+    let bounds: Vec<String> =
+        pred.type_bound_list()?.bounds().map(|it| it.syntax().text().to_string()).collect();
+    if bounds.len() < 2 {
+        return None;
+    }
+
+    ctx.add_action(AssistId("split_bounds"), "split bounds", |edit| {
+        let range = pred.syntax().range();
+        edit.target(range);
+        let preds: Vec<String> =
+            bounds.iter().map(|bound| format!("{}: {}", subject, bound)).collect();
+        edit.replace(range, preds.join(", "));
+        edit.set_cursor(range.start());
+    });
+
+    ctx.build()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::{check_assist, check_assist_target, check_assist_not_applicable};
+
+    #[test]
+    fn split_bounds_of_type() {
+        check_assist(
+            split_bounds,
+            "fn f<T>() where T: Fo<|>o + Iterator<Item = u8> + ?Sized {}",
+            "fn f<T>() where <|>T: Foo, T: Iterator<Item = u8>, T: ?Sized {}",
+        );
+    }
+
+    #[test]
+    fn split_bounds_of_lifetime() {
+        check_assist(
+            split_bounds,
+            "fn f<'a, 'b, 'c>() where 'a: 'b <|>+ 'c {}",
+            "fn f<'a, 'b, 'c>() where <|>'a: 'b, 'a: 'c {}",
+        );
+    }
+
+    #[test]
+    fn split_bounds_target() {
+        check_assist_target(split_bounds, "fn f<T>() where T: Foo<|> + Bar {}", "T: Foo + Bar");
+    }
+
+    #[test]
+    fn split_bounds_not_applicable_to_single_bound() {
+        check_assist_not_applicable(split_bounds, "fn f<T>() where T: Fo<|>o {}");
+    }
+}
@@ -1,17 +1,17 @@
-use std::sync::Arc;
+use std::{iter::successors, sync::Arc};
 
 use ra_db::{CrateId, SourceRootId, Edition};
-use ra_syntax::{ast::self, TreeArc};
+use ra_syntax::{AstNode, ast::{self, NameOwner, VisibilityOwner}, TreeArc};
 
 use crate::{
-    Name, Ty, HirFileId, Either,
+    Name, AsName, Ty, HirFileId, Either,
     HirDatabase, DefDatabase,
     type_ref::TypeRef,
     nameres::{ModuleScope, Namespace, ImportId, CrateModuleId},
     expr::{Body, BodySourceMap, validation::ExprValidator},
-    ty::{ TraitRef, InferenceResult},
+    ty::{ TraitRef, InferenceResult, InferenceMode},
     adt::{EnumVariantId, StructFieldId, VariantDef},
-    generics::HasGenericParams,
+    generics::{HasGenericParams, GenericParams},
     docs::{Documentation, Docs, docs_from_ast},
     ids::{FunctionId, StructId, EnumId, AstItemDef, ConstId, StaticId, TraitId, TypeAliasId},
     impl_block::ImplBlock,
@@ -271,7 +271,7 @@ impl Struct {
         self.id.source(db)
     }
 
-    pub fn module(&self, db: &impl HirDatabase) -> Module {
+    pub fn module(&self, db: &impl DefDatabase) -> Module {
         self.id.module(db)
     }
 
@@ -314,7 +314,7 @@ impl Struct {
         let r = self.module(db).resolver(db);
         // ...and add generic params, if present
         let p = self.generic_params(db);
-        let r = if !p.params.is_empty() { r.push_generic_params_scope(p) } else { r };
+        let r = if !p.is_empty() { r.push_generic_params_scope(p) } else { r };
         r
     }
 }
@@ -335,7 +335,7 @@ impl Enum {
         self.id.source(db)
     }
 
-    pub fn module(&self, db: &impl HirDatabase) -> Module {
+    pub fn module(&self, db: &impl DefDatabase) -> Module {
         self.id.module(db)
     }
 
@@ -370,7 +370,7 @@ impl Enum {
         let r = self.module(db).resolver(db);
         // ...and add generic params, if present
         let p = self.generic_params(db);
-        let r = if !p.params.is_empty() { r.push_generic_params_scope(p) } else { r };
+        let r = if !p.is_empty() { r.push_generic_params_scope(p) } else { r };
         r
     }
 }
@@ -442,6 +442,16 @@ impl DefWithBody {
         db.infer(*self)
     }
 
+    /// Infers the body without caching the result, which is mostly useful
+    /// for a quick `InferenceMode::Fast` pass.
+    pub fn infer_with_mode(
+        &self,
+        db: &impl HirDatabase,
+        mode: InferenceMode,
+    ) -> Arc<InferenceResult> {
+        crate::ty::infer_with_mode(db, *self, mode)
+    }
+
     pub fn body(&self, db: &impl HirDatabase) -> Arc<Body> {
         db.body_hir(*self)
     }
@@ -550,6 +560,20 @@ impl Function {
         }
     }
 
+    /// Whether this function can be used from `module`. Private functions are
+    /// visible in the module they are defined in and its descendants, and trait
+    /// methods are as visible as their trait.
+    // FIXME: restricted visibilities like `pub(super)` are treated like
+    // `pub(crate)` for now.
+    pub fn is_visible_from(&self, db: &impl DefDatabase, module: Module) -> bool {
+        match self.parent_trait(db) {
+            Some(trait_) => {
+                is_visible_from(db, trait_.module(db), trait_.source(db).1.visibility(), module)
+            }
+            None => is_visible_from(db, self.module(db), self.source(db).1.visibility(), module),
+        }
+    }
+
     // FIXME: move to a more general type for 'body-having' items
     /// Builds a resolver for code inside this item.
     pub(crate) fn resolver(&self, db: &impl HirDatabase) -> Resolver {
@@ -557,7 +581,7 @@ impl Function {
         let r = self.container(db).map_or_else(|| self.module(db).resolver(db), |c| c.resolver(db));
         // ...and add generic params, if present
         let p = self.generic_params(db);
-        let r = if !p.params.is_empty() { r.push_generic_params_scope(p) } else { r };
+        let r = if !p.is_empty() { r.push_generic_params_scope(p) } else { r };
         r
     }
 
@@ -569,6 +593,19 @@ impl Function {
     }
 }
 
+fn is_visible_from(
+    db: &impl DefDatabase,
+    defining_module: Module,
+    visibility: Option<&ast::Visibility>,
+    module: Module,
+) -> bool {
+    match visibility {
+        Some(vis) if vis.syntax().text() == "pub" => true,
+        Some(_) => defining_module.krate(db) == module.krate(db),
+        None => successors(Some(module), |m| m.parent(db)).any(|m| m == defining_module),
+    }
+}
+
 impl Docs for Function {
     fn docs(&self, db: &impl HirDatabase) -> Option<Documentation> {
         docs_from_ast(&*self.source(db).1)
@@ -699,15 +736,62 @@ impl Trait {
         db.trait_data(self)
     }
 
+    /// Whether this is an auto trait like `Send`, declared as `auto trait`.
+    pub fn is_auto(self, db: &impl DefDatabase) -> bool {
+        self.trait_data(db).is_auto()
+    }
+
     pub fn trait_ref(self, db: &impl HirDatabase) -> TraitRef {
         TraitRef::for_trait(db, self)
     }
 
+    /// Whether `dyn Trait` is allowed for this trait. It isn't if the trait
+    /// requires `Self: Sized`, has associated consts, or has methods that are
+    /// generic, have no `self` param or use `Self` in their signature other
+    /// than in the receiver. Methods with a `where Self: Sized` clause don't
+    /// count, as they can't be called on trait objects anyway.
+    // FIXME: look into the supertraits, and use the `sized` lang item
+    pub fn is_object_safe(self, db: &impl HirDatabase) -> bool {
+        let self_type_ref = TypeRef::Path(Name::self_type().into());
+        let requires_sized = |params: &GenericParams| {
+            params.where_predicates.iter().any(|pred| {
+                let is_sized = pred.trait_ref.segments.last().map_or(false, |it| {
+                    it.args_and_bindings.is_none() && it.name.to_string() == "Sized"
+                });
+                pred.type_ref == self_type_ref && is_sized
+            })
+        };
+        if requires_sized(&self.generic_params(db)) {
+            return false;
+        }
+        self.items(db).into_iter().all(|item| match item {
+            TraitItem::Const(_) => false,
+            TraitItem::TypeAlias(_) => true,
+            TraitItem::Function(f) => {
+                let generics = f.generic_params(db);
+                if requires_sized(&generics) {
+                    return true;
+                }
+                let sig = f.signature(db);
+                if !sig.has_self_param() || !generics.params.is_empty() {
+                    return false;
+                }
+                // the receiver is the first param
+                let mut types = sig.params()[1..].iter().chain(Some(sig.ret_type()));
+                !types.any(|type_ref| {
+                    let mut mentions_self = false;
+                    type_ref.walk(&mut |it| mentions_self |= *it == self_type_ref);
+                    mentions_self
+                })
+            }
+        })
+    }
+
     pub(crate) fn resolver(&self, db: &impl DefDatabase) -> Resolver {
-        let r = self.module(db).resolver(db);
+        let r = self.module(db).resolver(db).push_trait_scope(*self);
         // add generic params, if present
         let p = self.generic_params(db);
-        let r = if !p.params.is_empty() { r.push_generic_params_scope(p) } else { r };
+        let r = if !p.is_empty() { r.push_generic_params_scope(p) } else { r };
         r
     }
 }
@@ -732,6 +816,10 @@ impl TypeAlias {
         self.id.module(db)
     }
 
+    pub fn name(&self, db: &impl DefDatabase) -> Option<Name> {
+        self.source(db).1.name().map(|it| it.as_name())
+    }
+
     /// The containing impl block, if this is a method.
     pub fn impl_block(&self, db: &impl DefDatabase) -> Option<ImplBlock> {
         let module_impls = db.impls_in_module(self.module(db));
@@ -766,7 +854,7 @@ impl TypeAlias {
             .unwrap_or_else(|| self.module(db).resolver(db));
         // ...and add generic params, if present
         let p = self.generic_params(db);
-        let r = if !p.params.is_empty() { r.push_generic_params_scope(p) } else { r };
+        let r = if !p.is_empty() { r.push_generic_params_scope(p) } else { r };
         r
     }
 }
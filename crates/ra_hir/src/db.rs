@@ -1,7 +1,7 @@
 use std::sync::{Arc, Mutex};
 
 use ra_syntax::{SyntaxNode, TreeArc, SourceFile, SmolStr, ast};
-use ra_db::{SourceDatabase, salsa};
+use ra_db::{SourceDatabase, FileId, salsa};
 
 use crate::{
     HirFileId, MacroDefId, AstIdMap, ErasedFileAstId, Crate, Module, MacroCallLoc,
@@ -14,7 +14,7 @@ use crate::{
     ty::{InferenceResult, Ty, method_resolution::CrateImplBlocks, TypableDef, CallableDef, FnSig, TypeCtor},
     adt::{StructData, EnumData},
     impl_block::{ModuleImplBlocks, ImplSourceMap, ImplBlock},
-    generics::{GenericParams, GenericDef},
+    generics::{GenericParams, GenericParamsSourceMap, GenericDef},
     type_ref::TypeRef,
     traits::TraitData,
     lang_item::{LangItems, LangItemTarget},
@@ -99,9 +99,18 @@ pub trait DefDatabase: SourceDatabase {
         module: Module,
     ) -> (Arc<ModuleImplBlocks>, Arc<ImplSourceMap>);
 
+    #[salsa::invoke(crate::generics::GenericParams::generic_params_with_source_map_query)]
+    fn generic_params_with_source_map(
+        &self,
+        def: GenericDef,
+    ) -> (Arc<GenericParams>, Arc<GenericParamsSourceMap>);
+
     #[salsa::invoke(crate::generics::GenericParams::generic_params_query)]
     fn generic_params(&self, def: GenericDef) -> Arc<GenericParams>;
 
+    #[salsa::invoke(crate::generics::file_generic_defs_query)]
+    fn file_generic_defs(&self, file_id: FileId) -> Vec<GenericDef>;
+
     #[salsa::invoke(crate::FnSignature::fn_signature_query)]
     fn fn_signature(&self, func: Function) -> Arc<FnSignature>;
 
@@ -135,3 +135,114 @@ impl Diagnostic for MissingFields {
         self
     }
 }
+
+#[derive(Debug)]
+pub struct QuestionMarkErrorConversion {
+    pub file: HirFileId,
+    pub expr: SyntaxNodePtr,
+    pub from: String,
+    pub to: String,
+}
+
+impl Diagnostic for QuestionMarkErrorConversion {
+    fn message(&self) -> String {
+        format!("`?` can't convert the error type `{}` into `{}`", self.from, self.to)
+    }
+    fn file(&self) -> HirFileId {
+        self.file
+    }
+    fn syntax_node_ptr(&self) -> SyntaxNodePtr {
+        self.expr
+    }
+    fn as_any(&self) -> &(dyn Any + Send + 'static) {
+        self
+    }
+}
+
+#[derive(Debug)]
+pub struct AmbiguousAssociatedType {
+    pub file: HirFileId,
+    pub pat: SyntaxNodePtr,
+    pub name: Name,
+}
+
+impl Diagnostic for AmbiguousAssociatedType {
+    fn message(&self) -> String {
+        format!("ambiguous associated type `{}`", self.name)
+    }
+    fn file(&self) -> HirFileId {
+        self.file
+    }
+    fn syntax_node_ptr(&self) -> SyntaxNodePtr {
+        self.pat
+    }
+    fn as_any(&self) -> &(dyn Any + Send + 'static) {
+        self
+    }
+}
+
+#[derive(Debug)]
+pub struct MissingAssocTypeBinding {
+    pub file: HirFileId,
+    pub pat: SyntaxNodePtr,
+    pub name: Name,
+}
+
+impl Diagnostic for MissingAssocTypeBinding {
+    fn message(&self) -> String {
+        format!("the value of the associated type `{}` must be specified", self.name)
+    }
+    fn file(&self) -> HirFileId {
+        self.file
+    }
+    fn syntax_node_ptr(&self) -> SyntaxNodePtr {
+        self.pat
+    }
+    fn as_any(&self) -> &(dyn Any + Send + 'static) {
+        self
+    }
+}
+
+#[derive(Debug)]
+pub struct CantUseGenericParamFromOuterItem {
+    pub file: HirFileId,
+    pub path: SyntaxNodePtr,
+    pub name: Name,
+}
+
+impl Diagnostic for CantUseGenericParamFromOuterItem {
+    fn message(&self) -> String {
+        format!("can't use the generic param `{}` of an outer item", self.name)
+    }
+    fn file(&self) -> HirFileId {
+        self.file
+    }
+    fn syntax_node_ptr(&self) -> SyntaxNodePtr {
+        self.path
+    }
+    fn as_any(&self) -> &(dyn Any + Send + 'static) {
+        self
+    }
+}
+
+#[derive(Debug)]
+pub struct UninferrableParam {
+    pub file: HirFileId,
+    pub param: SyntaxNodePtr,
+    pub name: Name,
+}
+
+impl Diagnostic for UninferrableParam {
+    fn message(&self) -> String {
+        format!("the type param `{}` can't be inferred from the arguments", self.name)
+    }
+    fn file(&self) -> HirFileId {
+        self.file
+    }
+    fn syntax_node_ptr(&self) -> SyntaxNodePtr {
+        self.param
+    }
+    fn as_any(&self) -> &(dyn Any + Send + 'static) {
+        self
+    }
+}
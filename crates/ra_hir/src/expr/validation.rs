@@ -1,15 +1,26 @@
 use std::sync::Arc;
 use rustc_hash::FxHashSet;
 
-use ra_syntax::ast::{AstNode, StructLit};
+use ra_syntax::{
+    SyntaxNode, SyntaxNodePtr,
+    SyntaxKind::{
+        FN_DEF, STRUCT_DEF, ENUM_DEF, TRAIT_DEF, TYPE_ALIAS_DEF, IMPL_BLOCK, CONST_DEF, STATIC_DEF,
+        ITEM_LIST,
+    },
+    ast::{self, AstNode, StructLit, NameOwner, TypeParamsOwner},
+};
 
 use crate::{
     expr::AstPtr,
     HirDatabase,
     Function,
     Name,
-    diagnostics::{DiagnosticSink, MissingFields},
+    AsName,
+    diagnostics::{
+        DiagnosticSink, MissingFields, UninferrableParam, CantUseGenericParamFromOuterItem,
+    },
     adt::AdtDef,
+    generics::{GenericDef, HasGenericParams},
     Path,
     ty::InferenceResult
 };
@@ -40,6 +51,8 @@ impl<'a, 'b> ExprValidator<'a, 'b> {
                 _ => (),
             }
         }
+        self.validate_uninferrable_params(db);
+        self.validate_outer_generic_params(db);
     }
 
     fn validate_struct_literal(
@@ -89,4 +102,101 @@ impl<'a, 'b> ExprValidator<'a, 'b> {
             }
         }
     }
+
+    /// Reports the params of the function that can't be inferred, see
+    /// `GenericDef::uninferrable_params`.
+    fn validate_uninferrable_params(&mut self, db: &impl HirDatabase) {
+        let (file, source) = self.func.source(db);
+        for param in GenericDef::from(self.func).uninferrable_params(db) {
+            let type_param = source
+                .type_param_list()
+                .into_iter()
+                .flat_map(|it| it.type_params())
+                .find(|it| it.name().map(|it| it.as_name()).as_ref() == Some(param.name()));
+            if let Some(type_param) = type_param {
+                let param_ptr = SyntaxNodePtr::new(type_param.syntax());
+                let name = param.name().clone();
+                self.sink.push(UninferrableParam { file, param: param_ptr, name });
+            }
+        }
+    }
+
+    /// Reports the uses of the type params of the function and its parent in
+    /// the items nested in its body, like `T` in `fn inner(x: T) {}` inside of
+    /// `fn outer<T>()`. Nested items can't use the generic params of outer
+    /// items.
+    // FIXME: items in bodies aren't collected yet, so this is checked on the
+    // syntax level, and only for paths in type position
+    fn validate_outer_generic_params(&mut self, db: &impl HirDatabase) {
+        let (file, source) = self.func.source(db);
+        let body = match source.body() {
+            Some(it) => it,
+            None => return,
+        };
+        let generics = self.func.generic_params(db);
+        let outer_params = generics.params_including_parent();
+        if outer_params.is_empty() {
+            return;
+        }
+        for path_type in body.syntax().descendants().filter_map(ast::PathType::cast) {
+            let path = match path_type.path().and_then(Path::from_ast) {
+                Some(it) => it,
+                None => continue,
+            };
+            let name = match path.as_ident() {
+                Some(it) if outer_params.iter().any(|p| p.name() == it) => it,
+                _ => continue,
+            };
+            let mut in_nested_item = false;
+            // the name is resolved in the nearest nested item, which may
+            // declare a param of the same name; for associated items, the
+            // params of the impl or trait are in scope as well
+            let mut shadowed = false;
+            for node in path_type.syntax().ancestors().take_while(|it| *it != body.syntax()) {
+                let params = match nested_item_type_params(node) {
+                    Some(it) => it,
+                    None => continue,
+                };
+                in_nested_item = true;
+                if params.contains(name) {
+                    shadowed = true;
+                    break;
+                }
+                if !is_assoc_item(node) {
+                    break;
+                }
+            }
+            if in_nested_item && !shadowed {
+                let path_ptr = SyntaxNodePtr::new(path_type.syntax());
+                self.sink.push(CantUseGenericParamFromOuterItem {
+                    file,
+                    path: path_ptr,
+                    name: name.clone(),
+                });
+            }
+        }
+    }
+}
+
+/// The names of the type params of `node` if it is an item, or `None` if it
+/// isn't one.
+fn nested_item_type_params(node: &SyntaxNode) -> Option<Vec<Name>> {
+    let list = match node.kind() {
+        FN_DEF => ast::FnDef::cast(node)?.type_param_list(),
+        STRUCT_DEF => ast::StructDef::cast(node)?.type_param_list(),
+        ENUM_DEF => ast::EnumDef::cast(node)?.type_param_list(),
+        TRAIT_DEF => ast::TraitDef::cast(node)?.type_param_list(),
+        TYPE_ALIAS_DEF => ast::TypeAliasDef::cast(node)?.type_param_list(),
+        IMPL_BLOCK => ast::ImplBlock::cast(node)?.type_param_list(),
+        CONST_DEF | STATIC_DEF => None,
+        _ => return None,
+    };
+    let params = list.into_iter().flat_map(|it| it.type_params());
+    Some(params.filter_map(|it| it.name()).map(|it| it.as_name()).collect())
+}
+
+/// Whether the item `node` is in the item list of an impl or trait.
+fn is_assoc_item(node: &SyntaxNode) -> bool {
+    let container = node.parent().filter(|it| it.kind() == ITEM_LIST).and_then(|it| it.parent());
+    container.map_or(false, |it| it.kind() == IMPL_BLOCK || it.kind() == TRAIT_DEF)
 }
@@ -3,21 +3,90 @@
 //! generic parameters. See also the `Generics` type and the `generics_of` query
 //! in rustc.
 
-use std::sync::Arc;
+#[cfg(test)]
+mod tests;
 
-use ra_syntax::ast::{self, NameOwner, TypeParamsOwner, TypeBoundsOwner};
+use std::{
+    iter::{once, successors},
+    sync::Arc,
+};
+
+use rustc_hash::FxHashMap;
+use ra_db::FileId;
+use ra_syntax::{
+    AstNode, AstPtr, SyntaxNode, SyntaxToken,
+    SyntaxKind::{LIFETIME, FN_POINTER_TYPE, PARAM_LIST, RET_TYPE, PATH_SEGMENT},
+    ast::{
+        self, NameOwner, TypeParamsOwner, TypeBoundsOwner, TypeAscriptionOwner,
+        DefaultTypeParamOwner, VisibilityOwner,
+    },
+};
 
 use crate::{
-    db::DefDatabase,
-    Name, AsName, Function, Struct, Enum, Trait, TypeAlias, ImplBlock, Container, path::Path, type_ref::TypeRef, AdtDef
+    db::{DefDatabase, HirDatabase},
+    resolve::{Resolver, Resolution},
+    lang_item::LangItemTarget,
+    ty::{Ty, TypeCtor, Substs, TraitRef},
+    impl_block::ImplItem,
+    traits::TraitItem,
+    Crate, ModuleSource, StructField, FieldSource,
+    Name, AsName, Function, Struct, Enum, Trait, TypeAlias, ImplBlock, Container, Module, ModuleDef, path::{Path, GenericArg}, type_ref::{TypeRef, walk_path_mut}, AdtDef
 };
 
 /// Data about a generic parameter (to a function, struct, impl, ...).
+// FIXME: const params (`const N: usize`, and defaults referring to other
+// const params) aren't supported by the parser yet, so only type params are
+// lowered here.
 #[derive(Clone, PartialEq, Eq, Debug)]
 pub struct GenericParam {
     // FIXME: give generic params proper IDs
     pub(crate) idx: u32,
     pub(crate) name: Name,
+    pub(crate) default: Option<TypeRef>,
+}
+
+impl GenericParam {
+    pub fn idx(&self) -> u32 {
+        self.idx
+    }
+
+    pub fn name(&self) -> &Name {
+        &self.name
+    }
+
+    pub fn kind(&self) -> GenericParamKind {
+        GenericParamKind::Type
+    }
+}
+
+/// The kind of a declared generic parameter.
+// FIXME: add `Const` once const params are parsed and lowered
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum GenericParamKind {
+    Lifetime,
+    Type,
+}
+
+/// Data about a lifetime parameter. Besides the lifetimes declared explicitly
+/// in the parameter list, this also includes the anonymous lifetimes introduced
+/// by lifetime elision in function signatures.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct LifetimeParam {
+    pub(crate) idx: u32,
+    pub(crate) name: Name,
+    /// `true` if this lifetime was not declared in the source, but synthesized
+    /// for an elided lifetime (`&T` or `&'_ T`) in an input position.
+    pub(crate) is_elided: bool,
+}
+
+impl LifetimeParam {
+    pub fn name(&self) -> &Name {
+        &self.name
+    }
+
+    pub fn kind(&self) -> GenericParamKind {
+        GenericParamKind::Lifetime
+    }
 }
 
 /// Data about the generic parameters of a function, struct, impl, etc.
@@ -25,15 +94,84 @@ pub struct GenericParam {
 pub struct GenericParams {
     pub(crate) parent_params: Option<Arc<GenericParams>>,
     pub(crate) params: Vec<GenericParam>,
+    pub(crate) lifetimes: Vec<LifetimeParam>,
+    /// For functions with elided lifetimes in the return type: the index of
+    /// the lifetime these are tied to by the elision rules, if there is one.
+    pub(crate) elided_output_lifetime: Option<u32>,
     pub(crate) where_predicates: Vec<WherePredicate>,
+    pub(crate) lifetime_predicates: Vec<LifetimePredicate>,
 }
 
 /// A single predicate from a where clause, i.e. `where Type: Trait`. Combined
 /// where clauses like `where T: Foo + Bar` are turned into multiple of these.
+/// Inline bounds (`<T: Trait>`) and supertraits (`trait Sub: Super`, with
+/// `Self` as the subject) are lowered to predicates as well.
 #[derive(Clone, PartialEq, Eq, Debug)]
 pub struct WherePredicate {
-    type_ref: TypeRef,
-    trait_ref: Path,
+    pub(crate) type_ref: TypeRef,
+    pub(crate) trait_ref: Path,
+    /// The type args of the trait, like `Bar` and `Baz` in `T: Foo<Bar, Baz>`.
+    pub(crate) trait_args: Vec<TypeRef>,
+}
+
+impl WherePredicate {
+    pub fn trait_args(&self) -> &[TypeRef] {
+        &self.trait_args
+    }
+}
+
+/// A lifetime bound like `'b: 'a` or `T: 'a`, from the param list or a where
+/// clause. Combined bounds like `'c: 'a + 'b` are split like for
+/// `WherePredicate`s.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub(crate) struct LifetimePredicate {
+    pub(crate) subject: LifetimeBoundSubject,
+    pub(crate) lifetime: Name,
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub(crate) enum LifetimeBoundSubject {
+    Lifetime(Name),
+    Type(TypeRef),
+}
+
+/// Something in a type parameter list or where clause that could not be
+/// lowered, and was skipped (or, for missing names, lowered as a placeholder).
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum GenericsLoweringError {
+    /// A type parameter without a name.
+    MissingParamName { param: AstPtr<ast::TypeParam> },
+    /// A where predicate without a type or lifetime on its left-hand side.
+    MissingPredicateType { predicate: AstPtr<ast::WherePred> },
+    /// A bound that is not a path to a trait, e.g. a higher-ranked
+    /// `for<'a> Trait<'a>` bound.
+    UnsupportedBound { bound: AstPtr<ast::TypeBound> },
+    /// A default that refers back to the declaring type, possibly through the
+    /// defaults of other params, like `struct S<T = S<T>>`. Expanding it would
+    /// never terminate.
+    RecursiveDefault { param: AstPtr<ast::TypeParam> },
+    /// A supertrait that (possibly indirectly) has the trait itself as a
+    /// supertrait, like `B` in `trait A: B {}` with `trait B: A {}`.
+    SupertraitCycle { bound: AstPtr<ast::TypeBound> },
+}
+
+/// Maps `GenericParams` back to the source they were lowered from. The
+/// pointers are relative to the file of the item the params belong to.
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
+pub struct GenericParamsSourceMap {
+    params: FxHashMap<AstPtr<ast::TypeParam>, u32>,
+    errors: Vec<GenericsLoweringError>,
+}
+
+impl GenericParamsSourceMap {
+    /// The index of the param declared by the given type param node.
+    pub fn param_idx(&self, param: AstPtr<ast::TypeParam>) -> Option<u32> {
+        self.params.get(&param).cloned()
+    }
+
+    pub fn errors(&self) -> &[GenericsLoweringError] {
+        &self.errors
+    }
 }
 
 // FIXME: consts can have type parameters from their parents (i.e. associated consts of traits)
@@ -53,78 +191,552 @@ impl GenericParams {
         db: &impl DefDatabase,
         def: GenericDef,
     ) -> Arc<GenericParams> {
+        db.generic_params_with_source_map(def).0
+    }
+
+    pub(crate) fn generic_params_with_source_map_query(
+        db: &impl DefDatabase,
+        def: GenericDef,
+    ) -> (Arc<GenericParams>, Arc<GenericParamsSourceMap>) {
         let mut generics = GenericParams::default();
-        let parent = match def {
-            GenericDef::Function(it) => it.container(db).map(GenericDef::from),
-            GenericDef::TypeAlias(it) => it.container(db).map(GenericDef::from),
-            GenericDef::Struct(_) | GenericDef::Enum(_) | GenericDef::Trait(_) => None,
-            GenericDef::ImplBlock(_) => None,
-        };
-        generics.parent_params = parent.map(|p| db.generic_params(p));
+        let mut source_map = GenericParamsSourceMap::default();
+        generics.parent_params = def.generic_parent(db).map(|p| db.generic_params(p));
         let start = generics.parent_params.as_ref().map(|p| p.params.len()).unwrap_or(0) as u32;
         match def {
-            GenericDef::Function(it) => generics.fill(&*it.source(db).1, start),
-            GenericDef::Struct(it) => generics.fill(&*it.source(db).1, start),
-            GenericDef::Enum(it) => generics.fill(&*it.source(db).1, start),
+            GenericDef::Function(it) => {
+                let (_, node) = it.source(db);
+                generics.fill(&*node, start, &mut source_map);
+                generics.fill_elided_lifetimes(&*node);
+            }
+            GenericDef::Struct(it) => {
+                let (_, node) = it.source(db);
+                generics.fill(&*node, start, &mut source_map);
+                generics.check_recursive_defaults(
+                    db,
+                    it.module(db),
+                    it.into(),
+                    &*node,
+                    &mut source_map,
+                );
+            }
+            GenericDef::Enum(it) => {
+                let (_, node) = it.source(db);
+                generics.fill(&*node, start, &mut source_map);
+                generics.check_recursive_defaults(
+                    db,
+                    it.module(db),
+                    it.into(),
+                    &*node,
+                    &mut source_map,
+                );
+            }
             GenericDef::Trait(it) => {
                 // traits get the Self type as an implicit first type parameter
-                generics.params.push(GenericParam { idx: start, name: Name::self_type() });
-                generics.fill(&*it.source(db).1, start + 1);
+                generics.params.push(GenericParam {
+                    idx: start,
+                    name: Name::self_type(),
+                    default: None,
+                });
+                let (_, node) = it.source(db);
+                generics.fill(&*node, start + 1, &mut source_map);
+                generics.fill_supertraits(&*node, &mut source_map);
+                check_supertrait_cycles(db, it, &*node, &mut source_map);
+            }
+            GenericDef::TypeAlias(it) => {
+                let (_, node) = it.source(db);
+                generics.fill(&*node, start, &mut source_map);
+                generics.check_recursive_defaults(
+                    db,
+                    it.module(db),
+                    it.into(),
+                    &*node,
+                    &mut source_map,
+                );
             }
-            GenericDef::TypeAlias(it) => generics.fill(&*it.source(db).1, start),
-            GenericDef::ImplBlock(it) => generics.fill(&*it.source(db).1, start),
+            // FIXME: impls generated by `#[derive]` are not expanded yet, so
+            // there are no `ImplBlock`s for them. Once they are, their params
+            // should mirror those of the ADT they are derived for.
+            GenericDef::ImplBlock(it) => generics.fill(&*it.source(db).1, start, &mut source_map),
         }
 
-        Arc::new(generics)
+        (Arc::new(generics), Arc::new(source_map))
     }
 
-    fn fill(&mut self, node: &impl TypeParamsOwner, start: u32) {
+    fn fill(
+        &mut self,
+        node: &impl TypeParamsOwner,
+        start: u32,
+        source_map: &mut GenericParamsSourceMap,
+    ) {
         if let Some(params) = node.type_param_list() {
-            self.fill_params(params, start)
+            self.fill_lifetimes(params);
+            self.fill_params(params, start, source_map)
         }
         if let Some(where_clause) = node.where_clause() {
-            self.fill_where_predicates(where_clause);
+            self.fill_where_predicates(where_clause, source_map);
         }
     }
 
-    fn fill_params(&mut self, params: &ast::TypeParamList, start: u32) {
+    fn fill_params(
+        &mut self,
+        params: &ast::TypeParamList,
+        start: u32,
+        source_map: &mut GenericParamsSourceMap,
+    ) {
         for (idx, type_param) in params.type_params().enumerate() {
-            let name = type_param.name().map(AsName::as_name).unwrap_or_else(Name::missing);
-            let param = GenericParam { idx: idx as u32 + start, name };
+            let name = match type_param.name() {
+                Some(name) => name.as_name(),
+                None => {
+                    source_map.errors.push(GenericsLoweringError::MissingParamName {
+                        param: AstPtr::new(type_param),
+                    });
+                    Name::missing()
+                }
+            };
+            let default = type_param
+                .default_type()
+                .and_then(|it| ast::TypeRef::cast(it.syntax()))
+                .map(TypeRef::from_ast);
+            // inline bounds like `<T: Clone>` are treated like `where T: Clone`
+            let type_ref = TypeRef::Path(name.clone().into());
+            for bound in type_param.type_bound_list().iter().flat_map(|l| l.bounds()) {
+                self.add_where_predicate_from_bound(bound, type_ref.clone(), source_map);
+            }
+            let param = GenericParam { idx: idx as u32 + start, name, default };
+            source_map.params.insert(AstPtr::new(type_param), param.idx);
             self.params.push(param);
         }
     }
 
-    fn fill_where_predicates(&mut self, where_clause: &ast::WhereClause) {
+    fn check_recursive_defaults(
+        &self,
+        db: &impl DefDatabase,
+        module: Module,
+        item: ModuleDef,
+        node: &impl TypeParamsOwner,
+        source_map: &mut GenericParamsSourceMap,
+    ) {
+        let type_params = match node.type_param_list() {
+            Some(it) => it.type_params(),
+            None => return,
+        };
+        for (param, type_param) in self.params.iter().zip(type_params) {
+            let default = match &param.default {
+                Some(it) => it,
+                None => continue,
+            };
+            if self.refers_to_item(db, default, item, module, &mut vec![param.name.clone()]) {
+                let param = AstPtr::new(type_param);
+                source_map.errors.push(GenericsLoweringError::RecursiveDefault { param });
+            }
+        }
+    }
+
+    /// Checks whether `type_ref` mentions `item`, directly or through the
+    /// defaults of the params it mentions. Paths are resolved in `module`,
+    /// the module of the item, unless they name one of the params.
+    fn refers_to_item(
+        &self,
+        db: &impl DefDatabase,
+        type_ref: &TypeRef,
+        item: ModuleDef,
+        module: Module,
+        visited: &mut Vec<Name>,
+    ) -> bool {
+        let mut result = false;
+        type_ref.walk(&mut |type_ref| {
+            let path = match type_ref {
+                TypeRef::Path(path) => path,
+                _ => return,
+            };
+            let param = match path.as_ident().and_then(|name| self.find_by_name(name)) {
+                Some(it) => it,
+                None => {
+                    let def_map = db.crate_def_map(module.krate);
+                    let (resolved, remaining) = def_map.resolve_path(db, module.module_id, path);
+                    if remaining.is_none() && resolved.take_types() == Some(item) {
+                        result = true;
+                    }
+                    return;
+                }
+            };
+            if visited.contains(&param.name) {
+                return;
+            }
+            visited.push(param.name.clone());
+            if let Some(default) = &param.default {
+                result = result || self.refers_to_item(db, default, item, module, visited);
+            }
+        });
+        result
+    }
+
+    fn fill_lifetimes(&mut self, params: &ast::TypeParamList) {
+        for lifetime_param in params.lifetime_params() {
+            let name = lifetime_param
+                .lifetime_token()
+                .map(Name::from_lifetime_token)
+                .unwrap_or_else(Name::missing);
+            let bounds = lifetime_param
+                .syntax()
+                .children_with_tokens()
+                .filter_map(|it| it.as_token())
+                .filter(|it| it.kind() == LIFETIME)
+                .skip(1);
+            for bound in bounds {
+                self.lifetime_predicates.push(LifetimePredicate {
+                    subject: LifetimeBoundSubject::Lifetime(name.clone()),
+                    lifetime: Name::from_lifetime_token(bound),
+                });
+            }
+            self.push_lifetime(name, false);
+        }
+    }
+
+    /// Synthesizes anonymous lifetime params for the elided lifetimes in the
+    /// inputs of a function, and determines which lifetime elided lifetimes in
+    /// the output refer to, following the elision rules: if there is a `&self`
+    /// or `&mut self` parameter, its lifetime is used; otherwise, if there is
+    /// exactly one lifetime in the inputs, that one is used. If neither applies,
+    /// the output lifetime is left unelided.
+    fn fill_elided_lifetimes(&mut self, node: &ast::FnDef) {
+        let param_list = match node.param_list() {
+            Some(it) => it,
+            None => return,
+        };
+        let mut self_lifetime = None;
+        let mut input_lifetimes = Vec::new();
+        if let Some(self_param) = param_list.self_param() {
+            match self_param.ascribed_type() {
+                Some(type_ref) => {
+                    let lifetimes = self.input_lifetimes(type_ref);
+                    // `self: &Self` and `self: &mut Self` are just `&self`
+                    // and `&mut self` written out
+                    if is_reference_to_self(type_ref) {
+                        self_lifetime = lifetimes.first().cloned();
+                    }
+                    input_lifetimes.extend(lifetimes);
+                }
+                None if self_param.kind() != ast::SelfParamKind::Owned => {
+                    let lifetime = self.input_lifetime(self_param.lifetime_token());
+                    self_lifetime = lifetime;
+                    input_lifetimes.extend(lifetime);
+                }
+                None => {}
+            }
+        }
+        for param in param_list.params() {
+            if let Some(type_ref) = param.ascribed_type() {
+                input_lifetimes.extend(self.input_lifetimes(type_ref));
+            }
+        }
+        let ret_type = match node.ret_type().and_then(|it| it.type_ref()) {
+            Some(it) => it,
+            None => return,
+        };
+        let has_elided_output = references_in(ret_type).any(|it| is_elided(it.lifetime_token()));
+        if has_elided_output {
+            self.elided_output_lifetime = self_lifetime.or_else(|| {
+                if input_lifetimes.len() == 1 {
+                    input_lifetimes.first().cloned()
+                } else {
+                    None
+                }
+            });
+        }
+    }
+
+    fn input_lifetimes(&mut self, type_ref: &ast::TypeRef) -> Vec<u32> {
+        references_in(type_ref).filter_map(|it| self.input_lifetime(it.lifetime_token())).collect()
+    }
+
+    /// Returns the lifetime that a reference in an input position refers to,
+    /// synthesizing a new one if the lifetime was elided.
+    fn input_lifetime(&mut self, token: Option<SyntaxToken>) -> Option<u32> {
+        if is_elided(token) {
+            return Some(self.push_lifetime(Name::anonymous_lifetime(), true));
+        }
+        let name = token.map(Name::from_lifetime_token)?;
+        self.find_lifetime_by_name(&name).map(|it| it.idx)
+    }
+
+    fn push_lifetime(&mut self, name: Name, is_elided: bool) -> u32 {
+        let idx = (self.count_parent_lifetimes() + self.lifetimes.len()) as u32;
+        self.lifetimes.push(LifetimeParam { idx, name, is_elided });
+        idx
+    }
+
+    /// Lowers the supertraits of a trait, like `Super<Self>` in
+    /// `trait Sub: Super<Self>`, as predicates on the implicit `Self` param.
+    fn fill_supertraits(&mut self, node: &ast::TraitDef, source_map: &mut GenericParamsSourceMap) {
+        let self_type_ref = TypeRef::Path(Name::self_type().into());
+        for bound in node.type_bound_list().iter().flat_map(|l| l.bounds()) {
+            self.add_where_predicate_from_bound(bound, self_type_ref.clone(), source_map);
+        }
+    }
+
+    fn fill_where_predicates(
+        &mut self,
+        where_clause: &ast::WhereClause,
+        source_map: &mut GenericParamsSourceMap,
+    ) {
         for pred in where_clause.predicates() {
             let type_ref = match pred.type_ref() {
                 Some(type_ref) => type_ref,
-                None => continue,
+                None => {
+                    // lifetime predicates like `'a: 'b`
+                    let subject = pred
+                        .syntax()
+                        .children_with_tokens()
+                        .filter_map(|it| it.as_token())
+                        .find(|it| it.kind() == LIFETIME)
+                        .map(Name::from_lifetime_token);
+                    match subject {
+                        Some(subject) => {
+                            let bounds =
+                                pred.type_bound_list().into_iter().flat_map(|l| l.bounds());
+                            for lifetime in bounds.filter_map(lifetime_of_bound) {
+                                self.lifetime_predicates.push(LifetimePredicate {
+                                    subject: LifetimeBoundSubject::Lifetime(subject.clone()),
+                                    lifetime,
+                                });
+                            }
+                        }
+                        None => {
+                            source_map.errors.push(GenericsLoweringError::MissingPredicateType {
+                                predicate: AstPtr::new(pred),
+                            });
+                        }
+                    }
+                    continue;
+                }
             };
+            // FIXME: subjects like `[(); N]` are stored with the path of the
+            // length, but linking `N` to a const param has to wait until const
+            // params are parsed and lowered.
+            let type_ref = TypeRef::from_ast(type_ref);
             for bound in pred.type_bound_list().iter().flat_map(|l| l.bounds()) {
-                let path = bound
-                    .type_ref()
-                    .and_then(|tr| match tr.kind() {
-                        ast::TypeRefKind::PathType(path) => path.path(),
-                        _ => None,
+                self.add_where_predicate_from_bound(bound, type_ref.clone(), source_map);
+            }
+        }
+    }
+
+    fn add_where_predicate_from_bound(
+        &mut self,
+        bound: &ast::TypeBound,
+        type_ref: TypeRef,
+        source_map: &mut GenericParamsSourceMap,
+    ) {
+        // lifetime bounds like `T: 'a` have no type
+        let bound_type_ref = match bound.type_ref() {
+            Some(it) => it,
+            None => {
+                if let Some(lifetime) = lifetime_of_bound(bound) {
+                    let subject = LifetimeBoundSubject::Type(type_ref);
+                    self.lifetime_predicates.push(LifetimePredicate { subject, lifetime });
+                }
+                return;
+            }
+        };
+        let path = match bound_type_ref.kind() {
+            ast::TypeRefKind::PathType(path) => path.path(),
+            _ => None,
+        }
+        .and_then(Path::from_ast);
+        let path = match path {
+            Some(p) => p,
+            None => {
+                source_map
+                    .errors
+                    .push(GenericsLoweringError::UnsupportedBound { bound: AstPtr::new(bound) });
+                return;
+            }
+        };
+        let trait_args = path
+            .segments
+            .last()
+            .and_then(|it| it.args_and_bindings.as_ref())
+            .map(|it| {
+                it.args
+                    .iter()
+                    .map(|arg| match arg {
+                        GenericArg::Type(type_ref) => type_ref.clone(),
                     })
-                    .and_then(Path::from_ast);
-                let path = match path {
-                    Some(p) => p,
-                    None => continue,
-                };
-                self.where_predicates.push(WherePredicate {
-                    type_ref: TypeRef::from_ast(type_ref),
-                    trait_ref: path,
-                });
+                    .collect()
+            })
+            .unwrap_or_default();
+        self.where_predicates.push(WherePredicate { type_ref, trait_ref: path, trait_args });
+    }
+
+    /// Returns a copy of these params with the params renamed according to
+    /// `map`. References to renamed params in defaults and where predicates
+    /// are rewritten as well; the parent's params are left as they are.
+    pub fn with_renamed(&self, map: &FxHashMap<Name, Name>) -> GenericParams {
+        let mut rename = |type_ref: &mut TypeRef| {
+            let new_name = match type_ref {
+                TypeRef::Path(path) => path.as_ident().and_then(|name| map.get(name)),
+                _ => None,
+            };
+            if let Some(new_name) = new_name {
+                *type_ref = TypeRef::Path(new_name.clone().into());
             }
+        };
+        let mut result = self.clone();
+        for param in result.params.iter_mut() {
+            if let Some(new_name) = map.get(&param.name) {
+                param.name = new_name.clone();
+            }
+            if let Some(default) = &mut param.default {
+                default.walk_mut(&mut rename);
+            }
+        }
+        for pred in result.where_predicates.iter_mut() {
+            pred.type_ref.walk_mut(&mut rename);
+            walk_path_mut(&mut pred.trait_ref, &mut rename);
+            pred.trait_args.iter_mut().for_each(|it| it.walk_mut(&mut rename));
         }
+        for pred in result.lifetime_predicates.iter_mut() {
+            if let LifetimeBoundSubject::Type(type_ref) = &mut pred.subject {
+                type_ref.walk_mut(&mut rename);
+            }
+        }
+        result
+    }
+
+    /// Renders the own params back to the source of a param list, like
+    /// `<'a, T: Clone, U = i32>`, or an empty string if there are none. All
+    /// bounds on a param are written inline, including those that came from a
+    /// where clause; the predicates on other types are rendered by
+    /// `to_where_clause_source`.
+    pub fn to_param_list_source(&self, _db: &impl DefDatabase) -> String {
+        let lifetimes =
+            self.lifetimes.iter().filter(|lifetime| !lifetime.is_elided).map(|lifetime| {
+                let subject = LifetimeBoundSubject::Lifetime(lifetime.name.clone());
+                with_bounds(lifetime.name.to_string(), self.lifetime_bounds_of(&subject))
+            });
+        let params = self.params.iter().filter(|p| p.name != Name::self_type()).map(|param| {
+            let param_type_ref = TypeRef::Path(param.name.clone().into());
+            let bounds = self
+                .where_predicates
+                .iter()
+                .filter(|pred| pred.type_ref == param_type_ref)
+                .map(|pred| bound_source(&pred.trait_ref))
+                .chain(self.lifetime_bounds_of(&LifetimeBoundSubject::Type(param_type_ref)));
+            let mut text = with_bounds(param.name.to_string(), bounds);
+            if let Some(default) = &param.default {
+                text.push_str(&format!(" = {}", default));
+            }
+            text
+        });
+        let all: Vec<String> = lifetimes.chain(params).collect();
+        if all.is_empty() {
+            String::new()
+        } else {
+            format!("<{}>", all.join(", "))
+        }
+    }
+
+    /// Renders the predicates that `to_param_list_source` can't write inline,
+    /// i.e. those on types other than the own params, like
+    /// ` where Vec<T>: Debug`, or an empty string if there are none.
+    pub fn to_where_clause_source(&self, _db: &impl DefDatabase) -> String {
+        let is_own_param = |type_ref: &TypeRef| match type_ref {
+            TypeRef::Path(path) => path.as_ident().map_or(false, |name| {
+                *name != Name::self_type() && self.params.iter().any(|p| p.name == *name)
+            }),
+            _ => false,
+        };
+        let trait_preds = self
+            .where_predicates
+            .iter()
+            .filter(|pred| !is_own_param(&pred.type_ref))
+            .map(|pred| format!("{}: {}", pred.type_ref, bound_source(&pred.trait_ref)));
+        let lifetime_preds =
+            self.lifetime_predicates.iter().filter_map(|pred| match &pred.subject {
+                LifetimeBoundSubject::Type(type_ref) if !is_own_param(type_ref) => {
+                    Some(format!("{}: {}", type_ref, pred.lifetime))
+                }
+                _ => None,
+            });
+        let all: Vec<String> = trait_preds.chain(lifetime_preds).collect();
+        if all.is_empty() {
+            String::new()
+        } else {
+            format!(" where {}", all.join(", "))
+        }
+    }
+
+    fn lifetime_bounds_of<'a>(
+        &'a self,
+        subject: &'a LifetimeBoundSubject,
+    ) -> impl Iterator<Item = String> + 'a {
+        self.lifetime_predicates
+            .iter()
+            .filter(move |pred| pred.subject == *subject)
+            .map(|pred| pred.lifetime.to_string())
+    }
+
+    /// Dumps the params, lifetimes and where predicates (each including the
+    /// parent's, which come first) in a stable textual form, one per line, for
+    /// use in tests.
+    pub fn debug_dump(&self) -> String {
+        let mut generics: Vec<&GenericParams> =
+            successors(Some(self), |it| it.parent_params.as_ref().map(|it| &**it)).collect();
+        generics.reverse();
+        let mut lines = Vec::new();
+        for param in generics.iter().flat_map(|it| it.params.iter()) {
+            let default = param.default.as_ref().map(|it| format!(" = {}", it));
+            let default = default.unwrap_or_default();
+            lines.push(format!("param {} {}{}", param.idx, param.name, default));
+        }
+        for lifetime in generics.iter().flat_map(|it| it.lifetimes.iter()) {
+            let elided = if lifetime.is_elided { " (elided)" } else { "" };
+            lines.push(format!("lifetime {} {}{}", lifetime.idx, lifetime.name, elided));
+        }
+        for pred in generics.iter().flat_map(|it| it.where_predicates.iter()) {
+            lines.push(format!("where {}: {}", pred.type_ref, pred.trait_ref));
+        }
+        lines.join("\n")
+    }
+
+    /// Returns `true` if there are no type params, lifetime params or where
+    /// predicates, neither here nor in the parent's params.
+    pub fn is_empty(&self) -> bool {
+        self.params.is_empty()
+            && self.lifetimes.is_empty()
+            && self.where_predicates.is_empty()
+            && self.parent_params.as_ref().map_or(true, |p| p.is_empty())
     }
 
     pub(crate) fn find_by_name(&self, name: &Name) -> Option<&GenericParam> {
         self.params.iter().find(|p| &p.name == name)
     }
 
+    pub(crate) fn find_lifetime_by_name(&self, name: &Name) -> Option<&LifetimeParam> {
+        self.lifetimes
+            .iter()
+            .find(|p| &p.name == name)
+            .or_else(|| self.parent_params.as_ref()?.find_lifetime_by_name(name))
+    }
+
+    pub fn count_parent_lifetimes(&self) -> usize {
+        self.parent_params.as_ref().map(|p| p.count_lifetimes_including_parent()).unwrap_or(0)
+    }
+
+    pub fn count_lifetimes_including_parent(&self) -> usize {
+        self.count_parent_lifetimes() + self.lifetimes.len()
+    }
+
+    /// The number of lifetime args that a path to this item can be given,
+    /// including those of the parent, like 2 for `struct Ref<'a, 'b>`. Unlike
+    /// `count_lifetimes_including_parent`, lifetimes synthesized for elided
+    /// lifetimes aren't counted.
+    pub fn lifetime_arity_including_parent(&self) -> usize {
+        let parent_arity =
+            self.parent_params.as_ref().map(|p| p.lifetime_arity_including_parent()).unwrap_or(0);
+        parent_arity + self.lifetimes.iter().filter(|it| !it.is_elided).count()
+    }
+
     pub fn count_parent_params(&self) -> usize {
         self.parent_params.as_ref().map(|p| p.count_params_including_parent()).unwrap_or(0)
     }
@@ -134,6 +746,78 @@ impl GenericParams {
         parent_count + self.params.len()
     }
 
+    /// The number of type args that have to be given for this item, i.e. the
+    /// number of its own params without defaults. For traits, the implicit
+    /// `Self` param isn't counted.
+    pub fn min_type_args(&self) -> usize {
+        self.params.iter().filter(|p| p.default.is_none() && p.name != Name::self_type()).count()
+    }
+
+    /// The names of the own params that are also declared by a parent, like
+    /// the method's `T` in `impl<T> Foo<T> { fn f<T>() {} }`.
+    pub fn shadowed_parent_params(&self) -> Vec<Name> {
+        let parent_params = match &self.parent_params {
+            Some(it) => it.params_including_parent(),
+            None => return Vec::new(),
+        };
+        self.params
+            .iter()
+            .filter(|p| parent_params.iter().any(|parent| parent.name == p.name))
+            .map(|p| p.name.clone())
+            .collect()
+    }
+
+    /// Whether `self` and `other` declare the same number of params of each
+    /// kind, ignoring their names, defaults and bounds, like `<'a, T>` and
+    /// `<'b, U: Clone>`. Only the own params are compared, not the parents'.
+    pub fn is_compatible_with(&self, other: &GenericParams) -> bool {
+        let kind_counts = |params: &GenericParams| {
+            let types = params.params.iter().filter(|p| p.name != Name::self_type()).count();
+            (params.lifetimes.len(), types)
+        };
+        kind_counts(self) == kind_counts(other)
+    }
+
+    pub fn predicate_count_including_parent(&self) -> usize {
+        let parent_count =
+            self.parent_params.as_ref().map(|p| p.predicate_count_including_parent()).unwrap_or(0);
+        parent_count + self.where_predicates.len()
+    }
+
+    /// The where predicates whose subject is not just one of the declared type
+    /// params, e.g. `Vec<T>: Clone` (but not `T: Clone`).
+    pub fn concrete_predicates(&self) -> impl Iterator<Item = &WherePredicate> {
+        self.where_predicates.iter().filter(move |pred| !self.is_param(&pred.type_ref))
+    }
+
+    /// The params (including the parent's) mentioned anywhere in the subject of
+    /// `pred`, e.g. both `T` and `U` for `(T, &U): Clone`.
+    pub fn params_in_subject(&self, pred: &WherePredicate) -> Vec<&GenericParam> {
+        let params = self.params_including_parent();
+        let mut result = Vec::new();
+        pred.type_ref.walk(&mut |type_ref| {
+            let name = match type_ref {
+                TypeRef::Path(path) => path.as_ident(),
+                _ => None,
+            };
+            let param = name.and_then(|name| params.iter().find(|p| &p.name == name));
+            if let Some(param) = param {
+                if !result.contains(param) {
+                    result.push(*param);
+                }
+            }
+        });
+        result
+    }
+
+    fn is_param(&self, type_ref: &TypeRef) -> bool {
+        let name = match type_ref {
+            TypeRef::Path(path) => path.as_ident(),
+            _ => None,
+        };
+        name.map_or(false, |name| self.params_including_parent().iter().any(|p| &p.name == name))
+    }
+
     fn for_each_param<'a>(&'a self, f: &mut impl FnMut(&'a GenericParam)) {
         if let Some(parent) = &self.parent_params {
             parent.for_each_param(f);
@@ -146,6 +830,524 @@ impl GenericParams {
         self.for_each_param(&mut |p| vec.push(p));
         vec
     }
+
+    /// The substitution that maps each param (including the parent's) to
+    /// itself, for when there are no actual args, like for displaying a
+    /// signature on its own.
+    pub fn identity_substitution(&self) -> Substs {
+        Substs::identity(self)
+    }
+}
+
+fn check_supertrait_cycles(
+    db: &impl DefDatabase,
+    trait_: Trait,
+    node: &ast::TraitDef,
+    source_map: &mut GenericParamsSourceMap,
+) {
+    for bound in node.type_bound_list().iter().flat_map(|l| l.bounds()) {
+        let supertrait = match resolve_supertrait(db, trait_, bound) {
+            Some(it) => it,
+            None => continue,
+        };
+        if reaches_trait(db, supertrait, trait_, &mut Vec::new()) {
+            let bound = AstPtr::new(bound);
+            source_map.errors.push(GenericsLoweringError::SupertraitCycle { bound });
+        }
+    }
+}
+
+/// Checks whether `target` is `trait_` or one of its transitive supertraits.
+/// This works on the syntax instead of the lowered predicates, since the
+/// `generic_params` query of `target` may be the one asking.
+fn reaches_trait(
+    db: &impl DefDatabase,
+    trait_: Trait,
+    target: Trait,
+    visited: &mut Vec<Trait>,
+) -> bool {
+    if trait_ == target {
+        return true;
+    }
+    if visited.contains(&trait_) {
+        return false;
+    }
+    visited.push(trait_);
+    let (_, node) = trait_.source(db);
+    let supertraits: Vec<Trait> = node
+        .type_bound_list()
+        .iter()
+        .flat_map(|l| l.bounds())
+        .filter_map(|bound| resolve_supertrait(db, trait_, bound))
+        .collect();
+    supertraits.into_iter().any(|supertrait| reaches_trait(db, supertrait, target, visited))
+}
+
+/// Resolves a supertrait bound in the scope of the trait's module.
+fn resolve_supertrait(
+    db: &impl DefDatabase,
+    trait_: Trait,
+    bound: &ast::TypeBound,
+) -> Option<Trait> {
+    let path = match bound.type_ref()?.kind() {
+        ast::TypeRefKind::PathType(path) => Path::from_ast(path.path()?)?,
+        _ => return None,
+    };
+    let module = trait_.module(db);
+    let (def, segment_index) =
+        db.crate_def_map(module.krate).resolve_path(db, module.module_id, &path);
+    match (def.take_types()?, segment_index) {
+        (ModuleDef::Trait(it), None) => Some(it),
+        _ => None,
+    }
+}
+
+/// Whether `vis` makes an item part of the public API, i.e. is a plain `pub`.
+fn is_pub(vis: Option<&ast::Visibility>) -> bool {
+    vis.map_or(false, |it| it.syntax().text() == "pub")
+}
+
+/// The reference types in `type_ref`, except for those in nested binder
+/// scopes: the elided lifetimes in fn pointer types and `Fn(..)` bounds are
+/// tied to that signature, not to the one of the item.
+fn references_in(type_ref: &ast::TypeRef) -> impl Iterator<Item = &ast::ReferenceType> {
+    let root = type_ref.syntax();
+    root.descendants().filter_map(ast::ReferenceType::cast).filter(move |it| {
+        !it.syntax().ancestors().take_while(|it| *it != root).any(is_binder_scope)
+    })
+}
+
+fn is_binder_scope(node: &SyntaxNode) -> bool {
+    match node.kind() {
+        FN_POINTER_TYPE => true,
+        PARAM_LIST | RET_TYPE => node.parent().map_or(false, |it| it.kind() == PATH_SEGMENT),
+        _ => false,
+    }
+}
+
+fn is_reference_to_self(type_ref: &ast::TypeRef) -> bool {
+    match type_ref.kind() {
+        ast::TypeRefKind::ReferenceType(it) => {
+            it.type_ref().map_or(false, |it| it.syntax().text() == "Self")
+        }
+        _ => false,
+    }
+}
+
+fn lifetime_of_bound(bound: &ast::TypeBound) -> Option<Name> {
+    let token = bound.syntax().children_with_tokens().find(|it| it.kind() == LIFETIME)?;
+    token.as_token().map(Name::from_lifetime_token)
+}
+
+fn with_bounds(mut text: String, bounds: impl Iterator<Item = String>) -> String {
+    let bounds: Vec<String> = bounds.collect();
+    if !bounds.is_empty() {
+        text.push_str(&format!(": {}", bounds.join(" + ")));
+    }
+    text
+}
+
+/// Renders the path of a bound, with the args of the `Fn` traits sugared back
+/// like in the source, e.g. `Fn(u8) -> T` instead of `Fn<(u8,), Output = T>`.
+fn bound_source(path: &Path) -> String {
+    let last = match path.segments.last() {
+        Some(it) => it,
+        None => return path.to_string(),
+    };
+    let is_fn_trait = ["Fn", "FnMut", "FnOnce"].iter().any(|it| last.name.to_string() == *it);
+    let args = match &last.args_and_bindings {
+        Some(args) if is_fn_trait => args,
+        _ => return path.to_string(),
+    };
+    match (&args.args[..], &args.bindings[..]) {
+        ([GenericArg::Type(TypeRef::Tuple(params))], [(name, ret)]) if *name == Name::output() => {
+            let mut path = path.clone();
+            if let Some(it) = path.segments.last_mut() {
+                it.args_and_bindings = None;
+            }
+            let params: Vec<String> = params.iter().map(|it| it.to_string()).collect();
+            let ret = if *ret == TypeRef::unit() { String::new() } else { format!(" -> {}", ret) };
+            format!("{}({}){}", path, params.join(", "), ret)
+        }
+        _ => path.to_string(),
+    }
+}
+
+/// A reference lifetime is elided if it is either left out or written as `'_`.
+fn is_elided(token: Option<SyntaxToken>) -> bool {
+    match token {
+        None => true,
+        Some(token) => token.text().as_str() == "'_",
+    }
+}
+
+impl GenericDef {
+    /// The item this one inherits generic params from, i.e. the containing
+    /// impl or trait of an associated item.
+    pub fn generic_parent(self, db: &impl DefDatabase) -> Option<GenericDef> {
+        match self {
+            GenericDef::Function(it) => it.container(db).map(GenericDef::from),
+            GenericDef::TypeAlias(it) => it.container(db).map(GenericDef::from),
+            GenericDef::Struct(_) | GenericDef::Enum(_) | GenericDef::Trait(_) => None,
+            GenericDef::ImplBlock(_) => None,
+        }
+    }
+
+    /// All traits referenced in the bounds of this item and its parent, i.e.
+    /// in inline bounds, where clauses and supertraits, without duplicates.
+    pub fn referenced_traits(self, db: &impl HirDatabase) -> Vec<Trait> {
+        let resolver = self.resolver(db);
+        let mut traits = Vec::new();
+        let mut params = Some(db.generic_params(self));
+        while let Some(p) = params {
+            for pred in p.where_predicates.iter() {
+                let trait_ = match resolver.resolve_path(db, &pred.trait_ref).take_types() {
+                    Some(Resolution::Def(ModuleDef::Trait(it))) => it,
+                    _ => continue,
+                };
+                if !traits.contains(&trait_) {
+                    traits.push(trait_);
+                }
+            }
+            params = p.parent_params.clone();
+        }
+        traits
+    }
+
+    /// The crate that defines the trait of each of this item's own where
+    /// predicates, in the same order as the predicates; `None` for traits
+    /// that can't be resolved.
+    pub fn bound_trait_crates(self, db: &impl HirDatabase) -> Vec<Option<Crate>> {
+        let resolver = self.resolver(db);
+        db.generic_params(self)
+            .where_predicates
+            .iter()
+            .map(|pred| match resolver.resolve_path(db, &pred.trait_ref).take_types() {
+                Some(Resolution::Def(ModuleDef::Trait(it))) => it.module(db).krate(db),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// The own where predicates of this item as trait refs, with the subjects
+    /// resolved in the scope of the item, e.g. `Self::Item` in the where
+    /// clause of an impl becomes the impl's `type Item`. Predicates whose
+    /// trait can't be resolved are skipped.
+    pub fn bound_trait_refs(self, db: &impl HirDatabase) -> Vec<TraitRef> {
+        let resolver = self.resolver(db);
+        db.generic_params(self)
+            .where_predicates
+            .iter()
+            .filter_map(|pred| {
+                let self_ty = Ty::from_hir(db, &resolver, &pred.type_ref);
+                let trait_type_ref = TypeRef::Path(pred.trait_ref.clone());
+                TraitRef::from_hir(db, &resolver, &trait_type_ref, Some(self_ty))
+            })
+            .collect()
+    }
+
+    /// The type params of a struct or enum that are only used as arguments of
+    /// `PhantomData`, e.g. `T` in `struct S<T>(PhantomData<T>)`. Such params
+    /// don't influence the layout, but still are meaningful for variance and
+    /// drop checking.
+    pub fn phantom_only_params(self, db: &impl HirDatabase) -> Vec<GenericParam> {
+        let fields = match self {
+            GenericDef::Struct(it) => it.fields(db),
+            GenericDef::Enum(it) => it.variants(db).iter().flat_map(|v| v.fields(db)).collect(),
+            _ => return Vec::new(),
+        };
+        let phantom_data = self.module(db).krate(db).and_then(|krate| {
+            match db.lang_item(krate, "phantom_data".into())? {
+                LangItemTarget::Struct(it) => Some(it),
+                _ => None,
+            }
+        });
+        let phantom_data = match phantom_data {
+            Some(it) => it,
+            None => return Vec::new(),
+        };
+        let mut phantom_uses = Vec::new();
+        let mut other_uses = Vec::new();
+        for field in fields {
+            collect_param_uses(&field.ty(db), phantom_data, false, &mut |idx, in_phantom| {
+                if in_phantom {
+                    phantom_uses.push(idx)
+                } else {
+                    other_uses.push(idx)
+                }
+            });
+        }
+        db.generic_params(self)
+            .params
+            .iter()
+            .filter(|p| phantom_uses.contains(&p.idx) && !other_uses.contains(&p.idx))
+            .cloned()
+            .collect()
+    }
+
+    /// The type params of a struct or enum that look like intentional markers:
+    /// they are only used as arguments of `PhantomData`, and all their bounds
+    /// are auto traits, e.g. `T` in `struct S<T: Send>(PhantomData<T>)`. Such
+    /// params shouldn't be reported as unused.
+    pub fn marker_params(self, db: &impl HirDatabase) -> Vec<GenericParam> {
+        let resolver = self.resolver(db);
+        let generics = db.generic_params(self);
+        let bounded_by_auto_traits_only = |param: &GenericParam| {
+            generics
+                .where_predicates
+                .iter()
+                .filter(|pred| generics.params_in_subject(pred).iter().any(|p| p.idx == param.idx))
+                .all(|pred| match resolver.resolve_path(db, &pred.trait_ref).take_types() {
+                    Some(Resolution::Def(ModuleDef::Trait(it))) => it.is_auto(db),
+                    _ => false,
+                })
+        };
+        self.phantom_only_params(db).into_iter().filter(bounded_by_auto_traits_only).collect()
+    }
+
+    /// The type params of a struct or enum that are only used in private
+    /// positions, i.e. in non-`pub` fields and in the signatures of non-`pub`
+    /// inherent methods, like `T` in `struct S<T, U> { t: T, pub u: U }`.
+    /// Changing how these params are used doesn't affect the public API.
+    /// Restricted visibilities like `pub(crate)` count as private, and only
+    /// the impls of the defining crate are considered.
+    pub fn private_only_params(self, db: &impl HirDatabase) -> Vec<GenericParam> {
+        let (adt, fields): (AdtDef, Vec<(StructField, bool)>) = match self {
+            GenericDef::Struct(it) => {
+                let fields = it
+                    .fields(db)
+                    .into_iter()
+                    .map(|field| {
+                        let public = match field.source(db).1 {
+                            FieldSource::Named(it) => is_pub(it.visibility()),
+                            FieldSource::Pos(it) => is_pub(it.visibility()),
+                        };
+                        (field, public)
+                    })
+                    .collect();
+                (it.into(), fields)
+            }
+            // the fields of enum variants are always public
+            GenericDef::Enum(it) => (
+                it.into(),
+                it.variants(db).iter().flat_map(|v| v.fields(db)).map(|f| (f, true)).collect(),
+            ),
+            _ => return Vec::new(),
+        };
+        let mut public_uses = Vec::new();
+        let mut private_uses = Vec::new();
+        for (field, public) in fields {
+            field.ty(db).walk(&mut |ty| {
+                if let Ty::Param { idx, .. } = ty {
+                    if public {
+                        public_uses.push(*idx)
+                    } else {
+                        private_uses.push(*idx)
+                    }
+                }
+            });
+        }
+        if let Some(krate) = self.module(db).krate(db) {
+            let impls = db.impls_in_crate(krate);
+            let self_ty = Ty::apply(TypeCtor::Adt(adt), Substs::empty());
+            for impl_block in impls.lookup_impl_blocks(&self_ty) {
+                // the methods of trait impls are as public as the trait
+                let in_trait_impl = impl_block.target_trait(db).is_some();
+                let target_args = match impl_block.target_ty(db) {
+                    Ty::Apply(a_ty) => a_ty.parameters,
+                    _ => continue,
+                };
+                for item in impl_block.items(db) {
+                    let func = match item {
+                        ImplItem::Method(it) => it,
+                        _ => continue,
+                    };
+                    let public = in_trait_impl || is_pub(func.source(db).1.visibility());
+                    let sig = db.callable_item_signature(func.into());
+                    for ty in sig.params().iter().chain(once(sig.ret())) {
+                        // an impl param stands for the struct params it is
+                        // the argument of, like `U` in `impl<U> S<u32, U>`
+                        ty.walk(&mut |ty| {
+                            if let Ty::Param { .. } = ty {
+                                let uses =
+                                    if public { &mut public_uses } else { &mut private_uses };
+                                for (idx, arg) in target_args.iter().enumerate() {
+                                    if arg == ty {
+                                        uses.push(idx as u32);
+                                    }
+                                }
+                            }
+                        });
+                    }
+                }
+            }
+        }
+        db.generic_params(self)
+            .params
+            .iter()
+            .filter(|p| private_uses.contains(&p.idx) && !public_uses.contains(&p.idx))
+            .cloned()
+            .collect()
+    }
+
+    /// The own type params of a function that no argument determines, so that
+    /// callers always have to give them explicitly, like `T` in
+    /// `fn make<T>() -> T`. Besides the params in the types of the arguments,
+    /// the values of the associated types in the bounds of those params are
+    /// determined, like `U` in `fn f<T: Iterator<Item = U>, U>(t: T)`.
+    pub fn uninferrable_params(self, db: &impl HirDatabase) -> Vec<GenericParam> {
+        let func = match self {
+            GenericDef::Function(it) => it,
+            _ => return Vec::new(),
+        };
+        let generics = db.generic_params(self);
+        let params_in = |type_ref: &TypeRef| {
+            let mut names = Vec::new();
+            type_ref.walk(&mut |type_ref| {
+                let name = match type_ref {
+                    TypeRef::Path(path) => path.as_ident(),
+                    _ => None,
+                };
+                if let Some(name) = name.filter(|it| generics.find_by_name(it).is_some()) {
+                    names.push(name.clone());
+                }
+            });
+            names
+        };
+        let mut determined: Vec<Name> =
+            func.signature(db).params().iter().flat_map(|it| params_in(it)).collect();
+        loop {
+            let mut changed = false;
+            for pred in generics.where_predicates.iter() {
+                if !params_in(&pred.type_ref).iter().all(|it| determined.contains(it)) {
+                    continue;
+                }
+                let args =
+                    pred.trait_ref.segments.last().and_then(|it| it.args_and_bindings.as_ref());
+                for (_, type_ref) in args.iter().flat_map(|it| it.bindings.iter()) {
+                    for name in params_in(type_ref) {
+                        if !determined.contains(&name) {
+                            determined.push(name);
+                            changed = true;
+                        }
+                    }
+                }
+            }
+            if !changed {
+                break;
+            }
+        }
+        generics
+            .params
+            .iter()
+            .filter(|p| p.default.is_none() && !determined.contains(&p.name))
+            .cloned()
+            .collect()
+    }
+
+    pub(crate) fn resolver(self, db: &impl HirDatabase) -> Resolver {
+        match self {
+            GenericDef::Function(it) => it.resolver(db),
+            GenericDef::Struct(it) => it.resolver(db),
+            GenericDef::Enum(it) => it.resolver(db),
+            GenericDef::Trait(it) => it.resolver(db),
+            GenericDef::TypeAlias(it) => it.resolver(db),
+            GenericDef::ImplBlock(it) => it.resolver(db),
+        }
+    }
+
+    pub fn module(self, db: &impl DefDatabase) -> Module {
+        match self {
+            GenericDef::Function(it) => it.module(db),
+            GenericDef::Struct(it) => it.module(db),
+            GenericDef::Enum(it) => it.module(db),
+            GenericDef::Trait(it) => it.module(db),
+            GenericDef::TypeAlias(it) => it.module(db),
+            GenericDef::ImplBlock(it) => it.module(),
+        }
+    }
+}
+
+/// Salsa query. All items of the file `file_id` that can have generic params,
+/// including the items of its impls and traits and of inline modules.
+pub(crate) fn file_generic_defs_query(db: &impl DefDatabase, file_id: FileId) -> Vec<GenericDef> {
+    let source_root_id = db.file_source_root(file_id);
+    let root = db.source_root_crates(source_root_id).iter().find_map(|&crate_id| {
+        let krate = Crate { crate_id };
+        let module_id = db.crate_def_map(krate).find_module_by_source(file_id.into(), None)?;
+        Some(Module { krate, module_id })
+    });
+    let mut defs = Vec::new();
+    let mut modules: Vec<Module> = root.into_iter().collect();
+    while let Some(module) = modules.pop() {
+        for decl in module.declarations(db) {
+            match decl {
+                ModuleDef::Function(it) => defs.push(it.into()),
+                ModuleDef::Struct(it) => defs.push(it.into()),
+                ModuleDef::Enum(it) => defs.push(it.into()),
+                ModuleDef::TypeAlias(it) => defs.push(it.into()),
+                ModuleDef::Trait(it) => {
+                    defs.push(it.into());
+                    for item in it.items(db) {
+                        match item {
+                            TraitItem::Function(it) => defs.push(it.into()),
+                            TraitItem::TypeAlias(it) => defs.push(it.into()),
+                            TraitItem::Const(_) => {}
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+        for (impl_id, _) in db.impls_in_module(module).impls.iter() {
+            let impl_block = ImplBlock::from_id(module, impl_id);
+            defs.push(impl_block.into());
+            for item in impl_block.items(db) {
+                match item {
+                    ImplItem::Method(it) => defs.push(it.into()),
+                    ImplItem::TypeAlias(it) => defs.push(it.into()),
+                    ImplItem::Const(_) => {}
+                }
+            }
+        }
+        // `mod foo;` declares a module in another file
+        modules.extend(module.children(db).filter(|child| match child.definition_source(db).1 {
+            ModuleSource::Module(_) => true,
+            ModuleSource::SourceFile(_) => false,
+        }));
+    }
+    defs
+}
+
+/// Calls `f` with the index of every type param in `ty`, and whether it is
+/// (possibly deeply) inside the args of `phantom_data`.
+fn collect_param_uses(
+    ty: &Ty,
+    phantom_data: Struct,
+    in_phantom: bool,
+    f: &mut impl FnMut(u32, bool),
+) {
+    match ty {
+        Ty::Param { idx, .. } => f(*idx, in_phantom),
+        Ty::Apply(a_ty) => {
+            let in_phantom = in_phantom || a_ty.ctor == TypeCtor::Adt(AdtDef::Struct(phantom_data));
+            for ty in a_ty.parameters.iter() {
+                collect_param_uses(ty, phantom_data, in_phantom, f);
+            }
+        }
+        Ty::Bound(_) | Ty::Infer(_) | Ty::Unknown => {}
+    }
+}
+
+/// Finds the closest item that both `a` and `b` are or inherit generic params
+/// from, e.g. the impl block for two methods of the same impl.
+pub fn common_generic_parent(
+    db: &impl DefDatabase,
+    a: GenericDef,
+    b: GenericDef,
+) -> Option<GenericDef> {
+    let a_ancestors: Vec<GenericDef> = successors(Some(a), |it| it.generic_parent(db)).collect();
+    successors(Some(b), |it| it.generic_parent(db)).find(|it| a_ancestors.contains(it))
 }
 
 impl From<Container> for GenericDef {
@@ -0,0 +1,1092 @@
+use std::sync::Arc;
+
+use insta::assert_snapshot_matches;
+
+use crate::{
+    HirDisplay, ImplItem, ModuleDef, Ty,
+    mock::MockDatabase,
+    nameres::Namespace,
+    path::GenericArg,
+    source_binder,
+    traits::TraitItem,
+};
+
+use super::*;
+
+/// Finds the item with the given name among the declarations and impl items
+/// of the crate root, and returns its generic params.
+fn generic_params_of(db: &MockDatabase, name: &str) -> Arc<GenericParams> {
+    db.generic_params(find_def(db, name))
+}
+
+fn find_def(db: &MockDatabase, name: &str) -> GenericDef {
+    find_def_in_file(db, "/lib.rs", name)
+}
+
+fn find_def_in_file(db: &MockDatabase, path: &str, name: &str) -> GenericDef {
+    let file_id = db.file_id_of(path);
+    let module = source_binder::module_from_file_id(db, file_id).unwrap();
+    for decl in module.declarations(db) {
+        let (def_name, def): (Option<Name>, GenericDef) = match decl {
+            ModuleDef::Function(it) => (Some(it.name(db)), it.into()),
+            ModuleDef::Struct(it) => (it.name(db), it.into()),
+            ModuleDef::Enum(it) => (it.name(db), it.into()),
+            ModuleDef::Trait(it) => (it.name(db), it.into()),
+            _ => continue,
+        };
+        if def_name.map_or(false, |it| it.to_string() == name) {
+            return def;
+        }
+    }
+    for impl_block in module.impl_blocks(db) {
+        for item in impl_block.items(db) {
+            match item {
+                ImplItem::Method(f) if f.name(db).to_string() == name => return f.into(),
+                _ => {}
+            }
+        }
+    }
+    for decl in module.declarations(db) {
+        if let ModuleDef::Trait(trait_) = decl {
+            for item in trait_.items(db) {
+                match item {
+                    TraitItem::Function(f) if f.name(db).to_string() == name => return f.into(),
+                    _ => {}
+                }
+            }
+        }
+    }
+    panic!("no generic def named {}", name)
+}
+
+#[test]
+fn elided_lifetime_in_output_is_tied_to_single_input() {
+    let db = MockDatabase::with_files(
+        r"
+        //- /lib.rs
+        fn f(x: &str) -> &str { x }
+        ",
+    );
+    let params = generic_params_of(&db, "f");
+    assert_eq!(params.lifetimes.len(), 1);
+    assert!(params.lifetimes[0].is_elided);
+    assert_eq!(params.elided_output_lifetime, Some(params.lifetimes[0].idx));
+}
+
+#[test]
+fn elided_lifetime_in_output_with_multiple_inputs_is_unelided() {
+    let db = MockDatabase::with_files(
+        r"
+        //- /lib.rs
+        fn f(x: &str, y: &str) -> &str { x }
+        ",
+    );
+    let params = generic_params_of(&db, "f");
+    assert_eq!(params.lifetimes.len(), 2);
+    assert_eq!(params.elided_output_lifetime, None);
+}
+
+#[test]
+fn references_in_fn_pointers_and_fn_bounds_are_not_inputs() {
+    let db = MockDatabase::with_files(
+        r"
+        //- /lib.rs
+        fn f(x: &u8, g: fn(&u8) -> &u8, h: Box<dyn Fn(&u8) -> &u8>) -> &u8 { x }
+        ",
+    );
+    let params = generic_params_of(&db, "f");
+    assert_eq!(params.lifetimes.len(), 1);
+    assert_eq!(params.elided_output_lifetime, Some(params.lifetimes[0].idx));
+}
+
+#[test]
+fn explicit_self_reference_type_is_the_self_lifetime() {
+    let db = MockDatabase::with_files(
+        r"
+        //- /lib.rs
+        struct S;
+        impl S {
+            fn f(self: &Self, x: &u8) -> &u8 { x }
+            fn g(self: &mut Self, x: &u8) -> &u8 { x }
+        }
+        ",
+    );
+    for name in &["f", "g"] {
+        let params = generic_params_of(&db, name);
+        assert_eq!(params.lifetimes.len(), 2);
+        assert_eq!(params.elided_output_lifetime, Some(params.lifetimes[0].idx), "{}", name);
+    }
+}
+
+#[test]
+fn lifetime_arity_includes_parent_lifetimes() {
+    let db = MockDatabase::with_files(
+        r"
+        //- /lib.rs
+        struct Ref<'a, 'b>(&'a u8, &'b u8);
+        struct S<'a>(&'a u8);
+        impl<'a> S<'a> {
+            fn f<'b>(&self, x: &'b u8, y: &u8) {}
+        }
+        ",
+    );
+    assert_eq!(generic_params_of(&db, "Ref").lifetime_arity_including_parent(), 2);
+    let params = generic_params_of(&db, "f");
+    // the lifetimes of `&self` and `y` are elided
+    assert_eq!(params.lifetime_arity_including_parent(), 2);
+    assert_eq!(params.count_lifetimes_including_parent(), 4);
+}
+
+#[test]
+fn predicate_count_includes_parent_predicates() {
+    let db = MockDatabase::with_files(
+        r"
+        //- /lib.rs
+        trait Clone {}
+        trait Default {}
+        struct S<T>(T);
+        impl<T> S<T> where T: Clone + Default {
+            fn method<U>(&self, u: U) where U: Clone {}
+        }
+        ",
+    );
+    let params = generic_params_of(&db, "method");
+    assert_eq!(params.where_predicates.len(), 1);
+    assert_eq!(params.predicate_count_including_parent(), 3);
+}
+
+#[test]
+fn unlowerable_bound_is_recorded_as_error() {
+    let db = MockDatabase::with_files(
+        r"
+        //- /lib.rs
+        trait Foo<'a> {}
+        fn f<T>() where T: for<'a> Foo<'a>, T: 'static {}
+        ",
+    );
+    let (params, source_map) = db.generic_params_with_source_map(find_def(&db, "f"));
+    assert!(params.where_predicates.is_empty());
+    match source_map.errors() {
+        [GenericsLoweringError::UnsupportedBound { .. }] => {}
+        errors => panic!("unexpected errors: {:?}", errors),
+    }
+}
+
+#[test]
+fn concrete_predicates_skip_param_subjects() {
+    let db = MockDatabase::with_files(
+        r"
+        //- /lib.rs
+        trait Clone {}
+        struct Vec<T>(T);
+        fn f<T>() where T: Clone, Vec<T>: Clone {}
+        ",
+    );
+    let params = generic_params_of(&db, "f");
+    assert_eq!(params.where_predicates.len(), 2);
+    let concrete: Vec<_> = params.concrete_predicates().collect();
+    assert_eq!(concrete.len(), 1);
+    assert_eq!(concrete[0].type_ref, params.where_predicates[1].type_ref);
+}
+
+#[test]
+fn bound_of_async_fn_refers_to_its_param() {
+    let db = MockDatabase::with_files(
+        r"
+        //- /lib.rs
+        trait Send {}
+        async fn f<T: Send>(t: T) {}
+        ",
+    );
+    let params = generic_params_of(&db, "f");
+    assert_eq!(params.where_predicates.len(), 1);
+    let subject = match &params.where_predicates[0].type_ref {
+        TypeRef::Path(path) => path.as_ident().unwrap(),
+        type_ref => panic!("unexpected subject: {:?}", type_ref),
+    };
+    assert_eq!(params.find_by_name(subject).map(|it| it.idx), Some(0));
+}
+
+#[test]
+fn declared_params_report_their_kind() {
+    let db = MockDatabase::with_files(
+        r"
+        //- /lib.rs
+        fn f<'a, T, 'b, U>(x: &'a T, y: &'b U) {}
+        ",
+    );
+    let params = generic_params_of(&db, "f");
+    let lifetimes: Vec<_> = params.lifetimes.iter().map(|it| it.kind()).collect();
+    assert_eq!(lifetimes, vec![GenericParamKind::Lifetime, GenericParamKind::Lifetime]);
+    let types: Vec<_> = params.params.iter().map(|it| it.kind()).collect();
+    assert_eq!(types, vec![GenericParamKind::Type, GenericParamKind::Type]);
+}
+
+#[test]
+fn method_param_shadowing_impl_param() {
+    let db = MockDatabase::with_files(
+        r"
+        //- /lib.rs
+        struct Foo<T>(T);
+        impl<T, U> Foo<(T, U)> {
+            fn shadowing<T, V>(&self) {}
+            fn not_shadowing<V>(&self) {}
+        }
+        ",
+    );
+    let names: Vec<String> = generic_params_of(&db, "shadowing")
+        .shadowed_parent_params()
+        .iter()
+        .map(|it| it.to_string())
+        .collect();
+    assert_eq!(names, vec!["T"]);
+    assert!(generic_params_of(&db, "not_shadowing").shadowed_parent_params().is_empty());
+    assert!(generic_params_of(&db, "Foo").shadowed_parent_params().is_empty());
+}
+
+#[test]
+fn method_params_compatible_with_trait_method_params() {
+    let db = MockDatabase::with_files(
+        r"
+        //- /lib.rs
+        trait Tr {
+            fn one<T>();
+            fn two<T, U>();
+        }
+        struct S;
+        impl S {
+            fn method<A: Clone>() {}
+        }
+        ",
+    );
+    let method = generic_params_of(&db, "method");
+    assert!(method.is_compatible_with(&generic_params_of(&db, "one")));
+    assert!(!method.is_compatible_with(&generic_params_of(&db, "two")));
+}
+
+#[test]
+fn where_predicate_captures_trait_args() {
+    let db = MockDatabase::with_files(
+        r"
+        //- /lib.rs
+        trait Foo<A, B> {}
+        struct Bar;
+        struct Baz;
+        fn f<T>() where T: Foo<Bar, Baz> {}
+        ",
+    );
+    let params = generic_params_of(&db, "f");
+    let args: Vec<String> =
+        params.where_predicates[0].trait_args().iter().map(|it| it.to_string()).collect();
+    assert_eq!(args, vec!["Bar", "Baz"]);
+}
+
+#[test]
+fn params_only_in_return_type_are_uninferrable() {
+    let db = MockDatabase::with_files(
+        r"
+        //- /lib.rs
+        trait Iterator { type Item; }
+        fn make<T>() -> T { loop {} }
+        fn id<U>(u: U) -> U { u }
+        fn first<I: Iterator<Item = V>, V>(iter: I) -> V { loop {} }
+        ",
+    );
+    assert_snapshot_matches!(db.diagnostics(), @r###"
+"T": the type param `T` can't be inferred from the arguments
+"###
+    );
+}
+
+#[test]
+fn outer_generic_params_in_nested_items_are_diagnosed() {
+    let db = MockDatabase::with_files(
+        r"
+        //- /lib.rs
+        fn outer<T>(t: T) {
+            struct Local;
+            impl Local {
+                fn m(x: T) {}
+            }
+            fn shadowing<T>(x: T) {}
+            let y: T = t;
+        }
+        ",
+    );
+    assert_snapshot_matches!(db.diagnostics(), @r###"
+"T": can't use the generic param `T` of an outer item
+"###
+    );
+}
+
+#[test]
+fn outer_generic_param_is_not_shadowed_by_an_item_further_out() {
+    let db = MockDatabase::with_files(
+        r"
+        //- /lib.rs
+        fn outer<T>(t: T) {
+            fn middle<T>(x: T) {
+                fn inner(y: T) {}
+            }
+            impl<T> Trait for Wrapper<T> {
+                fn m(x: T) {}
+            }
+        }
+        ",
+    );
+    assert_snapshot_matches!(db.diagnostics(), @r###"
+"T": can't use the generic param `T` of an outer item
+"###
+    );
+}
+
+#[test]
+fn identity_substitution_maps_params_to_themselves() {
+    let db = MockDatabase::with_files(
+        r"
+        //- /lib.rs
+        struct Foo<T, U>(T, U);
+        ",
+    );
+    let params = generic_params_of(&db, "Foo");
+    let substs = params.identity_substitution();
+    let names: Vec<String> = params.params.iter().map(|it| it.name.to_string()).collect();
+    assert_eq!(names, vec!["T", "U"]);
+    assert_eq!(substs.len(), 2);
+    assert_eq!(substs[0], Ty::Param { idx: 0, name: params.params[0].name.clone() });
+    assert_eq!(substs[1], Ty::Param { idx: 1, name: params.params[1].name.clone() });
+}
+
+#[test]
+fn module_of_nested_generic_fn() {
+    let db = MockDatabase::with_files(
+        r"
+        //- /lib.rs
+        mod foo;
+        //- /foo.rs
+        mod bar;
+        //- /foo/bar.rs
+        fn f<T>(t: T) {}
+        ",
+    );
+    let def = find_def_in_file(&db, "/foo/bar.rs", "f");
+    let module = def.module(&db);
+    assert_eq!(module.name(&db).map(|it| it.to_string()), Some("bar".to_string()));
+    let parent = module.parent(&db).unwrap();
+    assert_eq!(parent.name(&db).map(|it| it.to_string()), Some("foo".to_string()));
+}
+
+#[test]
+fn recursive_default_is_recorded_as_error() {
+    let db = MockDatabase::with_files(
+        r"
+        //- /lib.rs
+        struct S<T = S<T>>(T);
+        struct Indirect<T = U, U = Option<Indirect<T>>>(T, U);
+        struct Fine<T = i32, U = Option<T>>(T, U);
+        struct Other<T = other::Other>(T);
+        struct Aliased<T = Box<self::Aliased>>(T);
+        mod other {
+            pub struct Other;
+        }
+        ",
+    );
+    let expected = &[("S", 1), ("Indirect", 2), ("Fine", 0), ("Other", 0), ("Aliased", 1)];
+    for (name, expected) in expected {
+        let (_, source_map) = db.generic_params_with_source_map(find_def(&db, name));
+        let recursive = source_map
+            .errors()
+            .iter()
+            .filter(|it| match it {
+                GenericsLoweringError::RecursiveDefault { .. } => true,
+                _ => false,
+            })
+            .count();
+        assert_eq!(recursive, *expected, "{}", name);
+    }
+}
+
+#[test]
+fn generic_param_from_position_in_param_name() {
+    let (db, position) = MockDatabase::with_position(
+        r"
+        //- /lib.rs
+        struct S;
+        impl S {
+            fn f<T, Fo<|>o: Clone>() {}
+        }
+        ",
+    );
+    let (def, param) = source_binder::generic_param_from_position(&db, position).unwrap();
+    assert_eq!(def, find_def(&db, "f"));
+    assert_eq!(param.name().to_string(), "Foo");
+    assert_eq!(param, db.generic_params(def).params[1]);
+}
+
+#[test]
+fn enclosing_generic_def_of_method_body() {
+    let (db, position) = MockDatabase::with_position(
+        r"
+        //- /lib.rs
+        struct S<T>(T);
+        impl<T> S<T> {
+            fn f<U>(&self) {
+                let x = 1<|>;
+            }
+        }
+        ",
+    );
+    let def = source_binder::find_enclosing_generic_def(&db, position.file_id, position.offset);
+    assert_eq!(def, Some(find_def(&db, "f")));
+}
+
+#[test]
+fn enclosing_generic_def_of_impl_header() {
+    let (db, position) = MockDatabase::with_position(
+        r"
+        //- /lib.rs
+        struct S<T>(T);
+        impl<T> S<T<|>> {
+            fn f<U>(&self) {}
+        }
+        ",
+    );
+    let module = source_binder::module_from_file_id(&db, position.file_id).unwrap();
+    let impl_block = module.impl_blocks(&db)[0];
+    let def = source_binder::find_enclosing_generic_def(&db, position.file_id, position.offset);
+    assert_eq!(def, Some(impl_block.into()));
+}
+
+#[test]
+fn param_list_source_includes_bounds_and_defaults() {
+    let db = MockDatabase::with_files(
+        r"
+        //- /lib.rs
+        trait Clone {}
+        trait Default {}
+        struct S<T: Clone, U = i32>(T, U);
+        struct R<'a, T: Clone, U = i32>(&'a T, U) where T: Default;
+        struct Empty;
+        ",
+    );
+    let source = |name| generic_params_of(&db, name).to_param_list_source(&db);
+    assert_eq!(source("S"), "<T: Clone, U = i32>");
+    assert_eq!(source("R"), "<'a, T: Clone + Default, U = i32>");
+    assert_eq!(source("Empty"), "");
+    assert_eq!(generic_params_of(&db, "R").to_where_clause_source(&db), "");
+}
+
+#[test]
+fn param_list_source_keeps_bounds_as_written() {
+    let db = MockDatabase::with_files(
+        r"
+        //- /lib.rs
+        trait Debug {}
+        struct Vec<T>(T);
+        struct S<'a, 'b: 'a, T: 'a + Debug, F: Fn(u8) -> T>(&'a T, &'b F)
+        where
+            Vec<T>: Debug,
+            F: 'b;
+        ",
+    );
+    let params = generic_params_of(&db, "S");
+    assert_eq!(
+        params.to_param_list_source(&db),
+        "<'a, 'b: 'a, T: Debug + 'a, F: Fn(u8) -> T + 'b>"
+    );
+    assert_eq!(params.to_where_clause_source(&db), " where Vec<T>: Debug");
+}
+
+#[test]
+fn param_list_source_of_renamed_params() {
+    let db = MockDatabase::with_files(
+        r"
+        //- /lib.rs
+        trait Clone {}
+        trait Debug {}
+        struct Vec<T>(T);
+        fn f<'a, T: Clone + 'a, F: FnOnce(T), V = Vec<T>>() where Vec<T>: Debug {}
+        fn g<U>() {}
+        ",
+    );
+    let params = generic_params_of(&db, "f");
+    let mut map = FxHashMap::default();
+    map.insert(params.params[0].name.clone(), generic_params_of(&db, "g").params[0].name.clone());
+    let renamed = params.with_renamed(&map);
+    assert_eq!(renamed.to_param_list_source(&db), "<'a, U: Clone + 'a, F: FnOnce(U), V = Vec<U>>");
+    assert_eq!(renamed.to_where_clause_source(&db), " where Vec<U>: Debug");
+}
+
+#[test]
+fn with_renamed_renames_params_and_predicates() {
+    let db = MockDatabase::with_files(
+        r"
+        //- /lib.rs
+        trait Clone {}
+        trait Into<T> {}
+        struct Vec<T>(T);
+        fn f<T: Clone, V>() where Vec<T>: Clone, V: Into<T> {}
+        fn g<U: Clone, V>() where Vec<U>: Clone, V: Into<U> {}
+        ",
+    );
+    let params = generic_params_of(&db, "f");
+    let expected = generic_params_of(&db, "g");
+    let mut map = FxHashMap::default();
+    map.insert(params.params[0].name.clone(), expected.params[0].name.clone());
+    assert_eq!(params.with_renamed(&map), *expected);
+}
+
+#[test]
+fn debug_dump_of_mixed_fn() {
+    let db = MockDatabase::with_files(
+        r"
+        //- /lib.rs
+        trait Clone {}
+        trait Into<T> {}
+        trait Iterator {}
+        struct S<'s, T>(&'s T);
+        impl<'s, T: Clone> S<'s, T> {
+            fn f<'a, U: Into<T>, I = Vec<U>>(&self, x: &'a U)
+            where I: Iterator<Item = &'a U> {}
+        }
+        ",
+    );
+    let params = generic_params_of(&db, "f");
+    assert_eq!(
+        params.debug_dump(),
+        "param 0 T
+param 1 U
+param 2 I = Vec<U>
+lifetime 0 's
+lifetime 1 'a
+lifetime 2 '_ (elided)
+where T: Clone
+where U: Into<T>
+where I: Iterator<Item = &U>"
+    );
+}
+
+#[test]
+fn supertrait_with_self_argument() {
+    let db = MockDatabase::with_files(
+        r"
+        //- /lib.rs
+        trait Super<T> {}
+        trait Sub: Super<Self> {}
+        ",
+    );
+    let trait_ = match find_def(&db, "Sub") {
+        GenericDef::Trait(it) => it,
+        def => panic!("unexpected def: {:?}", def),
+    };
+    let params = db.generic_params(trait_.into());
+    assert_eq!(params.debug_dump(), "param 0 Self\nwhere Self: Super<Self>");
+    let args = params.where_predicates[0].trait_ref.segments[0].args_and_bindings.clone().unwrap();
+    let arg = match &args.args[0] {
+        GenericArg::Type(type_ref) => type_ref,
+    };
+    let ty = Ty::from_hir(&db, &trait_.resolver(&db), arg);
+    assert_eq!(ty, Ty::Param { idx: 0, name: Name::self_type() });
+}
+
+#[test]
+fn object_safety_of_traits() {
+    let db = MockDatabase::with_files(
+        r"
+        //- /lib.rs
+        trait Sized {}
+        trait Safe {
+            fn f(&self) -> u32;
+            fn g(&self, x: u32);
+            fn new() -> Self where Self: Sized;
+        }
+        trait GenericMethod {
+            fn f<T>(&self, t: T);
+        }
+        trait ReturnsSelf {
+            fn f(&self) -> Option<Self>;
+        }
+        trait AssocConst {
+            const C: u32;
+        }
+        trait RequiresSized: Sized {}
+        ",
+    );
+    let is_object_safe = |name: &str| match find_def(&db, name) {
+        GenericDef::Trait(it) => it.is_object_safe(&db),
+        def => panic!("unexpected def: {:?}", def),
+    };
+    assert!(is_object_safe("Safe"));
+    assert!(!is_object_safe("GenericMethod"));
+    assert!(!is_object_safe("ReturnsSelf"));
+    assert!(!is_object_safe("AssocConst"));
+    assert!(!is_object_safe("RequiresSized"));
+}
+
+#[test]
+fn common_generic_parent_of_methods() {
+    let db = MockDatabase::with_files(
+        r"
+        //- /lib.rs
+        struct S<T>(T);
+        impl<T> S<T> {
+            fn a(&self) {}
+            fn b(&self) {}
+        }
+        impl S<u32> {
+            fn c(&self) {}
+        }
+        fn f() {}
+        ",
+    );
+    let a = find_def(&db, "a");
+    let impl_block = a.generic_parent(&db).unwrap();
+    match impl_block {
+        GenericDef::ImplBlock(_) => {}
+        def => panic!("unexpected parent: {:?}", def),
+    }
+    assert_eq!(common_generic_parent(&db, a, find_def(&db, "b")), Some(impl_block));
+    assert_eq!(common_generic_parent(&db, a, find_def(&db, "c")), None);
+    assert_eq!(common_generic_parent(&db, a, find_def(&db, "f")), None);
+}
+
+#[test]
+fn trait_method_bound_refers_to_trait_param() {
+    let db = MockDatabase::with_files(
+        r"
+        //- /lib.rs
+        trait From<T> {}
+        trait Tr<T> {
+            fn m<U: From<T>>();
+        }
+        ",
+    );
+    let def = find_def(&db, "m");
+    let params = db.generic_params(def);
+    assert_eq!(params.debug_dump(), "param 0 Self\nparam 1 T\nparam 2 U\nwhere U: From<T>");
+    let args = params.where_predicates[0].trait_ref.segments[0].args_and_bindings.clone().unwrap();
+    let arg = match &args.args[0] {
+        GenericArg::Type(type_ref) => type_ref,
+    };
+    let resolver = match def {
+        GenericDef::Function(it) => it.resolver(&db),
+        def => panic!("unexpected def: {:?}", def),
+    };
+    let trait_param = &params.params_including_parent()[1];
+    let ty = Ty::from_hir(&db, &resolver, arg);
+    assert_eq!(ty, Ty::Param { idx: trait_param.idx, name: trait_param.name.clone() });
+}
+
+#[test]
+fn is_empty_checks_parent_params() {
+    let db = MockDatabase::with_files(
+        r"
+        //- /lib.rs
+        struct S<T>(T);
+        impl<T> S<T> {
+            fn method(self) {}
+        }
+        fn f() {}
+        ",
+    );
+    assert!(generic_params_of(&db, "f").is_empty());
+    let params = generic_params_of(&db, "method");
+    assert!(params.params.is_empty());
+    assert!(!params.is_empty());
+}
+
+#[test]
+fn supertrait_cycles_are_reported() {
+    let db = MockDatabase::with_files(
+        r"
+        //- /lib.rs
+        trait A: B {}
+        trait B: A {}
+        trait C: D {}
+        trait D: E {}
+        trait E {}
+        ",
+    );
+    for (name, expected) in &[("A", 1), ("B", 1), ("C", 0), ("D", 0), ("E", 0)] {
+        let (_, source_map) = db.generic_params_with_source_map(find_def(&db, name));
+        let cycles = source_map
+            .errors()
+            .iter()
+            .filter(|it| match it {
+                GenericsLoweringError::SupertraitCycle { .. } => true,
+                _ => false,
+            })
+            .count();
+        assert_eq!(cycles, *expected, "{}", name);
+    }
+}
+
+#[test]
+fn impl_members_share_impl_params() {
+    let db = MockDatabase::with_files(
+        r"
+        //- /lib.rs
+        struct S<T>(T);
+        impl<T> S<T> {
+            fn f<U>() {}
+            type Alias = T;
+            const C: u32 = 0;
+        }
+        ",
+    );
+    let file_id = db.file_id_of("/lib.rs");
+    let module = source_binder::module_from_file_id(&db, file_id).unwrap();
+    let impl_block = module.impl_blocks(&db)[0];
+    let impl_params = db.generic_params(impl_block.into());
+    let items = impl_block.items(&db);
+    assert_eq!(items.len(), 3);
+    for item in items {
+        let params = impl_block.member_generic_params(&db, item);
+        assert_eq!(params.parent_params.as_ref(), Some(&impl_params), "{:?}", item);
+        assert_eq!(params.params_including_parent()[0].name().to_string(), "T");
+    }
+}
+
+#[test]
+fn const_type_in_impl_refers_to_impl_param() {
+    let db = MockDatabase::with_files(
+        r"
+        //- /lib.rs
+        struct Wrapper<T>(T);
+        struct S<T>(T);
+        impl<T> S<T> {
+            const C: Wrapper<T> = loop {};
+        }
+        ",
+    );
+    let file_id = db.file_id_of("/lib.rs");
+    let module = source_binder::module_from_file_id(&db, file_id).unwrap();
+    let impl_block = module.impl_blocks(&db)[0];
+    let impl_param = db.generic_params(impl_block.into()).params[0].clone();
+    let c = match impl_block.items(&db)[0] {
+        ImplItem::Const(it) => it,
+        item => panic!("expected a const, got {:?}", item),
+    };
+    match db.type_for_def(c.into(), Namespace::Values) {
+        Ty::Apply(a_ty) => {
+            assert_eq!(a_ty.parameters[0], Ty::Param { idx: impl_param.idx, name: impl_param.name })
+        }
+        ty => panic!("expected `Wrapper<T>`, got {:?}", ty),
+    }
+}
+
+#[test]
+fn nested_param_in_type_alias_refers_to_alias_param() {
+    let db = MockDatabase::with_files(
+        r"
+        //- /lib.rs
+        struct Vec<T>(T);
+        struct Inner<T>(T);
+        type Alias<T> = Vec<Inner<T>>;
+        ",
+    );
+    let file_id = db.file_id_of("/lib.rs");
+    let module = source_binder::module_from_file_id(&db, file_id).unwrap();
+    let alias = module
+        .declarations(&db)
+        .into_iter()
+        .find_map(|decl| match decl {
+            ModuleDef::TypeAlias(it) => Some(it),
+            _ => None,
+        })
+        .unwrap();
+    let alias_param = db.generic_params(alias.into()).params[0].clone();
+    match db.type_for_def(alias.into(), Namespace::Types) {
+        Ty::Apply(vec) => match &vec.parameters[0] {
+            Ty::Apply(inner) => assert_eq!(
+                inner.parameters[0],
+                Ty::Param { idx: alias_param.idx, name: alias_param.name }
+            ),
+            ty => panic!("expected `Inner<T>`, got {:?}", ty),
+        },
+        ty => panic!("expected `Vec<Inner<T>>`, got {:?}", ty),
+    }
+}
+
+#[test]
+fn min_type_args_skip_defaults_and_self() {
+    let db = MockDatabase::with_files(
+        r"
+        //- /lib.rs
+        struct S<T, U = i32>(T, U);
+        trait Tr<T> {}
+        ",
+    );
+    assert_eq!(generic_params_of(&db, "S").min_type_args(), 1);
+    assert_eq!(generic_params_of(&db, "Tr").min_type_args(), 1);
+}
+
+#[test]
+fn array_subject_predicate_is_stored() {
+    let db = MockDatabase::with_files(
+        r"
+        //- /lib.rs
+        trait Sized {}
+        const N: usize = 4;
+        fn f<T>() where [(); N]: Sized, [T; N]: Sized {}
+        ",
+    );
+    let params = generic_params_of(&db, "f");
+    assert_eq!(params.debug_dump(), "param 0 T\nwhere [(); N]: Sized\nwhere [T; N]: Sized");
+}
+
+#[test]
+fn referenced_traits_are_collected_once() {
+    let db = MockDatabase::with_files(
+        r"
+        //- /lib.rs
+        trait Clone {}
+        trait Debug {}
+        struct S<T>(T);
+        impl<T: Debug> S<T> {
+            fn f<U: Clone>(&self) where U: Debug, T: Clone {}
+        }
+        ",
+    );
+    let names: Vec<String> = find_def(&db, "f")
+        .referenced_traits(&db)
+        .into_iter()
+        .map(|it| it.name(&db).unwrap().to_string())
+        .collect();
+    assert_eq!(names, vec!["Clone", "Debug"]);
+}
+
+#[test]
+fn params_in_composite_subjects() {
+    let db = MockDatabase::with_files(
+        r"
+        //- /lib.rs
+        trait Clone {}
+        trait Copy {}
+        fn f<T, U, V>() where (T, U): Clone, &V: Copy {}
+        ",
+    );
+    let params = generic_params_of(&db, "f");
+    let names = |pred: &WherePredicate| -> Vec<String> {
+        params.params_in_subject(pred).iter().map(|it| it.name.to_string()).collect()
+    };
+    assert_eq!(names(&params.where_predicates[0]), vec!["T", "U"]);
+    assert_eq!(names(&params.where_predicates[1]), vec!["V"]);
+}
+
+#[test]
+fn self_assoc_type_bound_in_impl_resolves_to_impl_member() {
+    let db = MockDatabase::with_files(
+        r#"
+        //- /lib.rs
+        trait Clone {}
+        trait Iterator {
+            type Item;
+        }
+        struct S;
+        impl Iterator for S where Self::Item: Clone {
+            type Item = u32;
+        }
+        "#,
+    );
+    let file_id = db.file_id_of("/lib.rs");
+    let module = source_binder::module_from_file_id(&db, file_id).unwrap();
+    let impl_block = module.impl_blocks(&db)[0];
+    let bounds = GenericDef::from(impl_block).bound_trait_refs(&db);
+    assert_eq!(bounds.len(), 1);
+    assert_eq!(bounds[0].self_ty().display(&db).to_string(), "u32");
+    assert_eq!(bounds[0].trait_().name(&db).unwrap().to_string(), "Clone");
+}
+
+#[test]
+fn phantom_only_params_of_struct() {
+    let db = MockDatabase::with_files(
+        r#"
+        //- /lib.rs
+        #[lang = "phantom_data"]
+        struct PhantomData<T>;
+        struct S<T>(PhantomData<T>);
+        struct Mixed<T, U>(PhantomData<(T, U)>, U);
+        "#,
+    );
+    let names = |name: &str| -> Vec<String> {
+        find_def(&db, name).phantom_only_params(&db).iter().map(|it| it.name.to_string()).collect()
+    };
+    assert_eq!(names("S"), vec!["T"]);
+    assert_eq!(names("Mixed"), vec!["T"]);
+    assert!(names("PhantomData").is_empty());
+}
+
+#[test]
+fn marker_params_are_phantom_only_with_auto_trait_bounds() {
+    let db = MockDatabase::with_files(
+        r#"
+        //- /lib.rs
+        #[lang = "phantom_data"]
+        struct PhantomData<T>;
+        auto trait Send {}
+        trait Clone {}
+        struct Marker<T: Send>(PhantomData<T>);
+        struct Bounded<T: Clone>(PhantomData<T>);
+        struct Used<T: Send>(T);
+        "#,
+    );
+    let names = |name: &str| -> Vec<String> {
+        find_def(&db, name).marker_params(&db).iter().map(|it| it.name.to_string()).collect()
+    };
+    assert_eq!(names("Marker"), vec!["T"]);
+    assert!(names("Bounded").is_empty());
+    assert!(names("Used").is_empty());
+}
+
+#[test]
+fn private_only_params_of_struct() {
+    let db = MockDatabase::with_files(
+        r#"
+        //- /lib.rs
+        pub struct S<T, U, V> {
+            t: T,
+            u: U,
+            pub v: V,
+        }
+        impl<A, B, C> S<A, B, C> {
+            pub fn get(&self) -> &B {}
+            fn get_private(&self) -> &A {}
+        }
+        "#,
+    );
+    let names: Vec<String> =
+        find_def(&db, "S").private_only_params(&db).iter().map(|it| it.name.to_string()).collect();
+    assert_eq!(names, vec!["T"]);
+}
+
+#[test]
+fn fn_trait_object_output_refers_to_param() {
+    let db = MockDatabase::with_files(
+        r"
+        //- /lib.rs
+        trait Clone {}
+        fn f<T, U>() where Box<dyn FnMut(U) -> T>: Clone {}
+        ",
+    );
+    let params = generic_params_of(&db, "f");
+    let pred = &params.where_predicates[0];
+    assert_eq!(pred.type_ref.to_string(), "Box<dyn FnMut<(U,), Output = T>>");
+    let names: Vec<String> =
+        params.params_in_subject(pred).iter().map(|it| it.name.to_string()).collect();
+    assert_eq!(names, vec!["U", "T"]);
+}
+
+#[test]
+fn file_generic_defs_lists_all_items() {
+    let db = MockDatabase::with_files(
+        r"
+        //- /lib.rs
+        struct S<T>(T);
+        trait Tr {}
+        fn f() {}
+        fn g<T>() {}
+        const C: u32 = 0;
+        ",
+    );
+    let defs = db.file_generic_defs(db.file_id_of("/lib.rs"));
+    assert_eq!(defs.len(), 4);
+    for name in &["S", "Tr", "f", "g"] {
+        assert!(defs.contains(&find_def(&db, name)), "{} is missing", name);
+    }
+}
+
+#[test]
+fn param_in_macro_expanded_fn_resolves() {
+    let db = MockDatabase::with_files(
+        r"
+        //- /lib.rs
+        macro_rules! make_fn {
+            ($t:ty) => { fn f<T>(x: $t) {} }
+        }
+        make_fn!(T);
+        ",
+    );
+    let f = match find_def(&db, "f") {
+        GenericDef::Function(it) => it,
+        def => panic!("expected a function, got {:?}", def),
+    };
+    let param = generic_params_of(&db, "f").params[0].clone();
+    let sig = db.callable_item_signature(f.into());
+    assert_eq!(sig.params()[0], Ty::Param { idx: param.idx, name: param.name });
+}
+
+#[test]
+fn impl_target_type_is_kept_as_type_ref() {
+    let db = MockDatabase::with_files(
+        r"
+        //- /lib.rs
+        struct Foo<T>(T);
+        impl<T> Foo<T> {}
+        ",
+    );
+    let file_id = db.file_id_of("/lib.rs");
+    let module = source_binder::module_from_file_id(&db, file_id).unwrap();
+    let impl_block = module.impl_blocks(&db)[0];
+    assert_eq!(impl_block.target_type(&db).to_string(), "Foo<T>");
+}
+
+#[test]
+fn blanket_impls_are_detected() {
+    let db = MockDatabase::with_files(
+        r"
+        //- /lib.rs
+        trait Trait {}
+        struct Foo<T>(T);
+        impl<T> Trait for T {}
+        impl<T> Trait for Foo<T> {}
+        ",
+    );
+    let file_id = db.file_id_of("/lib.rs");
+    let module = source_binder::module_from_file_id(&db, file_id).unwrap();
+    let impl_blocks = module.impl_blocks(&db);
+    assert!(impl_blocks[0].is_blanket(&db));
+    assert!(!impl_blocks[1].is_blanket(&db));
+}
+
+#[test]
+fn self_type_params_of_trait_impl() {
+    let db = MockDatabase::with_files(
+        r"
+        //- /lib.rs
+        trait Foo<T> {}
+        struct Bar<U>(U);
+        impl<T, U> Foo<T> for Bar<U> {}
+        ",
+    );
+    let file_id = db.file_id_of("/lib.rs");
+    let module = source_binder::module_from_file_id(&db, file_id).unwrap();
+    let impl_block = module.impl_blocks(&db)[0];
+    let names: Vec<String> =
+        impl_block.self_type_params(&db).iter().map(|it| it.name.to_string()).collect();
+    assert_eq!(names, vec!["U"]);
+}
+
+#[test]
+fn bound_trait_crates_of_std_and_local_traits() {
+    let mut db = MockDatabase::with_files(
+        r"
+        //- /lib.rs
+        trait Local {}
+        fn f<T: std::clone::Clone, U: Local>() {}
+
+        //- /std.rs
+        pub mod clone {
+            pub trait Clone {}
+        }
+        ",
+    );
+    db.set_crate_graph_from_fixture(crate_graph! {
+        "lib": ("/lib.rs", ["std"]),
+        "std": ("/std.rs", []),
+    });
+    let file_id = db.file_id_of("/lib.rs");
+    let lib = source_binder::module_from_file_id(&db, file_id).unwrap().krate(&db).unwrap();
+    let std = lib.dependencies(&db)[0].krate;
+    assert_eq!(find_def(&db, "f").bound_trait_crates(&db), vec![Some(std), Some(lib)]);
+}
@@ -14,7 +14,7 @@ use crate::{
     ids::LocationCtx,
     resolve::Resolver,
     ty::Ty,
-    generics::HasGenericParams,
+    generics::{HasGenericParams, GenericParams, GenericParam},
     code_model_api::{Module, ModuleSource}
 };
 
@@ -93,11 +93,54 @@ impl ImplBlock {
         db.impls_in_module(self.module).impls[self.impl_id].items().to_vec()
     }
 
+    /// Whether this is a blanket impl, i.e. whether the self type is just one
+    /// of the impl's type params, like in `impl<T> Trait for T`.
+    pub fn is_blanket(&self, db: &impl DefDatabase) -> bool {
+        match self.target_type(db) {
+            TypeRef::Path(path) => path
+                .as_ident()
+                .map_or(false, |name| self.generic_params(db).find_by_name(name).is_some()),
+            _ => false,
+        }
+    }
+
+    /// The params of this impl that appear in its self type, like `U` in
+    /// `impl<T, U> Foo<T> for Bar<U>`, in the order of their declaration.
+    /// Params only appearing in the trait ref or the where clauses aren't
+    /// included.
+    pub fn self_type_params(&self, db: &impl HirDatabase) -> Vec<GenericParam> {
+        let mut idxs = Vec::new();
+        self.target_ty(db).walk(&mut |ty| {
+            if let Ty::Param { idx, .. } = ty {
+                idxs.push(*idx);
+            }
+        });
+        self.generic_params(db).params.iter().filter(|p| idxs.contains(&p.idx)).cloned().collect()
+    }
+
+    /// The generic params of an item of this impl, with the impl's params as
+    /// parent. Consts can't declare params of their own, so theirs just
+    /// consist of the impl's.
+    pub fn member_generic_params(
+        &self,
+        db: &impl DefDatabase,
+        member: ImplItem,
+    ) -> Arc<GenericParams> {
+        match member {
+            ImplItem::Method(it) => it.generic_params(db),
+            ImplItem::TypeAlias(it) => it.generic_params(db),
+            ImplItem::Const(_) => Arc::new(GenericParams {
+                parent_params: Some(self.generic_params(db)),
+                ..GenericParams::default()
+            }),
+        }
+    }
+
     pub(crate) fn resolver(&self, db: &impl DefDatabase) -> Resolver {
         let r = self.module().resolver(db);
         // add generic params, if present
         let p = self.generic_params(db);
-        let r = if !p.params.is_empty() { r.push_generic_params_scope(p) } else { r };
+        let r = if !p.is_empty() { r.push_generic_params_scope(p) } else { r };
         let r = r.push_impl_block_scope(self.clone());
         r
     }
@@ -4,7 +4,8 @@ use rustc_hash::FxHashMap;
 use ra_syntax::{SmolStr, ast::AttrsOwner};
 
 use crate::{
-    Crate, DefDatabase, Enum, Function, HirDatabase, ImplBlock, Module, Static, Struct, Trait
+    Crate, DefDatabase, Enum, Function, HirDatabase, ImplBlock, Module, ModuleDef, Static, Struct,
+    Trait,
 };
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -80,19 +81,29 @@ impl LangItems {
         let source = module.definition_source(db).1;
         for (impl_id, _) in impl_blocks.impls.iter() {
             let impl_block = source_map.get(&source, impl_id);
-            let lang_item_name = impl_block
-                .attrs()
-                .filter_map(|a| a.as_key_value())
-                .filter(|(key, _)| key == "lang")
-                .map(|(_, val)| val)
-                .nth(0);
-            if let Some(lang_item_name) = lang_item_name {
+            if let Some(lang_item_name) = lang_item_name(&*impl_block) {
                 let imp = ImplBlock::from_id(*module, impl_id);
                 self.items.entry(lang_item_name).or_insert(LangItemTarget::ImplBlock(imp));
             }
         }
 
-        // FIXME we should look for the other lang item targets (traits, structs, ...)
+        // Look for trait and struct targets
+        for def in module.declarations(db) {
+            let (lang_item_name, target) = match def {
+                ModuleDef::Trait(trait_) => {
+                    (lang_item_name(&*trait_.source(db).1), LangItemTarget::Trait(trait_))
+                }
+                ModuleDef::Struct(s) => {
+                    (lang_item_name(&*s.source(db).1), LangItemTarget::Struct(s))
+                }
+                _ => continue,
+            };
+            if let Some(lang_item_name) = lang_item_name {
+                self.items.entry(lang_item_name).or_insert(target);
+            }
+        }
+
+        // FIXME we should look for the other lang item targets (enums, fns, ...)
 
         // Look for lang items in the children
         for child in module.children(db) {
@@ -100,3 +111,11 @@ impl LangItems {
         }
     }
 }
+
+fn lang_item_name(node: &impl AttrsOwner) -> Option<SmolStr> {
+    node.attrs()
+        .filter_map(|a| a.as_key_value())
+        .filter(|(key, _)| key == "lang")
+        .map(|(_, val)| val)
+        .nth(0)
+}
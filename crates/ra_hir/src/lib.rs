@@ -59,16 +59,24 @@ pub use self::{
     either::Either,
     path::{Path, PathKind},
     name::Name,
+    type_ref::{TypeRef, Mutability, substitute_named},
     source_id::{AstIdMap, ErasedFileAstId},
     ids::{HirFileId, MacroDefId, MacroCallId, MacroCallLoc},
     nameres::{PerNs, Namespace, ImportId},
-    ty::{Ty, ApplicationTy, TypeCtor, TraitRef, Substs, display::HirDisplay, CallableDef},
+    ty::{
+        Ty, ApplicationTy, TypeCtor, TraitRef, Substs, display::HirDisplay, CallableDef,
+        InferenceMode,
+    },
     impl_block::{ImplBlock, ImplItem},
+    traits::TraitItem,
     docs::{Docs, Documentation},
     adt::AdtDef,
     expr::ExprScopes,
     resolve::Resolution,
-    generics::{GenericParams, GenericParam, HasGenericParams},
+    generics::{
+        GenericDef, GenericParams, GenericParam, GenericParamKind, LifetimeParam,
+        HasGenericParams, GenericParamsSourceMap, GenericsLoweringError, common_generic_parent,
+    },
     source_binder::{SourceAnalyzer, PathResolution, ScopeEntryWithSyntax,MacroByExampleDef},
 };
 
@@ -1,6 +1,6 @@
 use std::fmt;
 
-use ra_syntax::{ast, SmolStr};
+use ra_syntax::{ast, SmolStr, SyntaxToken};
 
 /// `Name` is a wrapper around string, which is used in hir for both references
 /// and declarations. In theory, names should also carry hygiene info, but we are
@@ -42,10 +42,61 @@ impl Name {
         Name::new("Self".into())
     }
 
+    /// The name of the `Target` associated type of `Deref`.
+    pub(crate) fn target() -> Name {
+        Name::new("Target".into())
+    }
+
+    /// The name of the `Item` associated type of `Iterator` and `IntoIterator`.
+    pub(crate) fn item() -> Name {
+        Name::new("Item".into())
+    }
+
+    /// The name of the `Output` associated type of the operator traits, like
+    /// `Add`.
+    pub(crate) fn output() -> Name {
+        Name::new("Output".into())
+    }
+
+    /// The segments of the path to the `IntoIterator` trait, i.e.
+    /// `std::iter::IntoIterator`.
+    pub(crate) fn into_iterator_path() -> Vec<Name> {
+        vec![Name::new("std".into()), Name::new("iter".into()), Name::new("IntoIterator".into())]
+    }
+
+    /// The names of the `Ok` and `Error` associated types of the `Try` trait.
+    pub(crate) fn ok() -> Name {
+        Name::new("Ok".into())
+    }
+
+    pub(crate) fn error() -> Name {
+        Name::new("Error".into())
+    }
+
+    /// The segments of the path to the `Try` trait used by `?`, i.e.
+    /// `std::ops::Try`.
+    pub(crate) fn try_path() -> Vec<Name> {
+        vec![Name::new("std".into()), Name::new("ops".into()), Name::new("Try".into())]
+    }
+
+    /// The segments of the path to the `From` trait, i.e. `std::convert::From`.
+    pub(crate) fn from_path() -> Vec<Name> {
+        vec![Name::new("std".into()), Name::new("convert".into()), Name::new("From".into())]
+    }
+
     pub(crate) fn tuple_field_name(idx: usize) -> Name {
         Name::new(idx.to_string().into())
     }
 
+    /// The name of a lifetime which was elided in the source (or written as `'_`).
+    pub(crate) fn anonymous_lifetime() -> Name {
+        Name::new("'_".into())
+    }
+
+    pub(crate) fn from_lifetime_token(token: SyntaxToken) -> Name {
+        Name::new(token.text().clone())
+    }
+
     // There's should be no way to extract a string out of `Name`: `Name` in the
     // future, `Name` will include hygiene information, and you can't encode
     // hygiene into a String.
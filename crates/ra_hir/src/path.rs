@@ -1,6 +1,9 @@
-use std::sync::Arc;
+use std::{fmt, sync::Arc};
 
-use ra_syntax::{ast::{self, NameOwner}, AstNode};
+use ra_syntax::{
+    ast::{self, NameOwner, TypeAscriptionOwner},
+    AstNode,
+};
 
 use crate::{Name, AsName, type_ref::TypeRef};
 
@@ -17,12 +20,13 @@ pub struct PathSegment {
 }
 
 /// Generic arguments to a path segment (e.g. the `i32` in `Option<i32>`). This
-/// can (in the future) also include bindings of associated types, like in
-/// `Iterator<Item = Foo>`.
+/// also includes bindings of associated types, like in `Iterator<Item = Foo>`.
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct GenericArgs {
     pub args: Vec<GenericArg>,
-    // someday also bindings
+    /// The associated type bindings, like the `Item = Foo` in
+    /// `Iterator<Item = Foo>`.
+    pub bindings: Vec<(Name, TypeRef)>,
 }
 
 /// A single generic argument.
@@ -66,8 +70,11 @@ impl Path {
 
             match segment.kind()? {
                 ast::PathSegmentKind::Name(name) => {
-                    let args =
-                        segment.type_arg_list().and_then(GenericArgs::from_ast).map(Arc::new);
+                    let args = segment
+                        .type_arg_list()
+                        .and_then(GenericArgs::from_ast)
+                        .or_else(|| GenericArgs::from_fn_like_path_ast(segment))
+                        .map(Arc::new);
                     let segment = PathSegment { name: name.as_name(), args_and_bindings: args };
                     segments.push(segment);
                 }
@@ -104,6 +111,21 @@ impl Path {
         }
     }
 
+    /// Constructs a path without generic args from the given segment names,
+    /// e.g. `::std::iter::IntoIterator`.
+    pub(crate) fn from_simple_segments(
+        kind: PathKind,
+        segments: impl IntoIterator<Item = Name>,
+    ) -> Path {
+        Path {
+            kind,
+            segments: segments
+                .into_iter()
+                .map(|name| PathSegment { name, args_and_bindings: None })
+                .collect(),
+        }
+    }
+
     /// Converts an `ast::NameRef` into a single-identifier `Path`.
     pub fn from_name_ref(name_ref: &ast::NameRef) -> Path {
         name_ref.as_name().into()
@@ -127,6 +149,16 @@ impl Path {
         self.segments.first().map(|s| &s.name)
     }
 
+    /// The `n`th type argument of the last segment, e.g. `bool` for `n == 1` in
+    /// `Foo<'a, i32, bool>`. Lifetime arguments aren't counted, so this matches
+    /// the order of the type params in `GenericParams`.
+    pub fn nth_type_arg(&self, n: usize) -> Option<TypeRef> {
+        let args = self.segments.last()?.args_and_bindings.as_ref()?;
+        match args.args.get(n)? {
+            GenericArg::Type(type_ref) => Some(type_ref.clone()),
+        }
+    }
+
     pub fn expand_macro_expr(&self) -> Option<Name> {
         self.as_ident().and_then(|name| Some(name.clone()))
     }
@@ -139,13 +171,37 @@ impl GenericArgs {
             let type_ref = TypeRef::from_ast_opt(type_arg.type_ref());
             args.push(GenericArg::Type(type_ref));
         }
-        // lifetimes and assoc type args ignored for now
-        if args.len() > 0 {
-            Some(GenericArgs { args })
+        let mut bindings = Vec::new();
+        for assoc_type_arg in node.assoc_type_args() {
+            if let Some(name_ref) = assoc_type_arg.name_ref() {
+                let type_ref = TypeRef::from_ast_opt(assoc_type_arg.type_ref());
+                bindings.push((name_ref.as_name(), type_ref));
+            }
+        }
+        // lifetimes ignored for now
+        if args.len() > 0 || bindings.len() > 0 {
+            Some(GenericArgs { args, bindings })
         } else {
             None
         }
     }
+
+    /// Lowers the parenthesized args of the `Fn` traits, like in
+    /// `FnMut(u8) -> T`, to their desugared form, i.e. a tuple of the params
+    /// and an `Output` binding: `FnMut<(u8,), Output = T>`.
+    pub(crate) fn from_fn_like_path_ast(segment: &ast::PathSegment) -> Option<GenericArgs> {
+        let params = segment.param_list()?;
+        let param_types =
+            params.params().map(|it| TypeRef::from_ast_opt(it.ascribed_type())).collect();
+        let ret_type = match segment.ret_type() {
+            Some(it) => TypeRef::from_ast_opt(it.type_ref()),
+            None => TypeRef::unit(),
+        };
+        Some(GenericArgs {
+            args: vec![GenericArg::Type(TypeRef::Tuple(param_types))],
+            bindings: vec![(Name::output(), ret_type)],
+        })
+    }
 }
 
 impl From<Name> for Path {
@@ -157,6 +213,37 @@ impl From<Name> for Path {
     }
 }
 
+impl fmt::Display for Path {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let prefix = match self.kind {
+            PathKind::Plain => None,
+            PathKind::Self_ => Some("self"),
+            PathKind::Super => Some("super"),
+            PathKind::Crate => Some("crate"),
+            PathKind::Abs => Some(""),
+        };
+        let mut parts: Vec<String> = prefix.into_iter().map(String::from).collect();
+        for segment in self.segments.iter() {
+            let args = match &segment.args_and_bindings {
+                Some(args) => {
+                    let args = args
+                        .args
+                        .iter()
+                        .map(|arg| match arg {
+                            GenericArg::Type(type_ref) => type_ref.to_string(),
+                        })
+                        .chain(args.bindings.iter().map(|(name, ty)| format!("{} = {}", name, ty)))
+                        .collect::<Vec<_>>();
+                    format!("<{}>", args.join(", "))
+                }
+                None => String::new(),
+            };
+            parts.push(format!("{}{}", segment.name, args));
+        }
+        write!(f, "{}", parts.join("::"))
+    }
+}
+
 fn expand_use_tree<'a>(
     prefix: Option<Path>,
     tree: &'a ast::UseTree,
@@ -236,3 +323,20 @@ fn convert_path(prefix: Option<Path>, path: &ast::Path) -> Option<Path> {
     };
     Some(res)
 }
+
+#[cfg(test)]
+mod tests {
+    use ra_syntax::SourceFile;
+
+    use super::*;
+
+    #[test]
+    fn nth_type_arg_skips_lifetimes() {
+        let file = SourceFile::parse("type X = Foo<'a, i32, bool>;");
+        let path = file.syntax().descendants().find_map(ast::Path::cast).unwrap();
+        let path = Path::from_ast(path).unwrap();
+        assert_eq!(path.nth_type_arg(0).unwrap().to_string(), "i32");
+        assert_eq!(path.nth_type_arg(1).unwrap().to_string(), "bool");
+        assert_eq!(path.nth_type_arg(2), None);
+    }
+}
@@ -14,7 +14,7 @@ use crate::{
     db::HirDatabase,
     name::{Name, KnownName},
     nameres::{PerNs, CrateDefMap, CrateModuleId},
-    generics::GenericParams,
+    generics::{GenericParams, WherePredicate},
     expr::{scope::{ExprScopes, ScopeId}, PatId},
     impl_block::ImplBlock,
     path::Path,
@@ -107,6 +107,8 @@ pub(crate) enum Scope {
     GenericParams(Arc<GenericParams>),
     /// Brings `Self` into scope
     ImplBlockScope(ImplBlock),
+    /// The items of a trait; `Self` is brought into scope by its generic params
+    TraitScope(Trait),
     /// Local bindings
     ExprScope(ExprScope),
 }
@@ -193,6 +195,40 @@ impl Resolver {
         names
     }
 
+    /// The names of the declared lifetime params in scope, innermost first.
+    pub(crate) fn lifetimes_in_scope<'a>(&'a self) -> impl Iterator<Item = &'a Name> + 'a {
+        self.scopes
+            .iter()
+            .rev()
+            .flat_map(|scope| {
+                match scope {
+                    Scope::GenericParams(gp) => Some(gp.lifetimes.iter()),
+                    _ => None,
+                }
+                .into_iter()
+            })
+            .flatten()
+            .filter(|lifetime| !lifetime.is_elided)
+            .map(|lifetime| &lifetime.name)
+    }
+
+    /// The where predicates (including inline bounds) of the generic params in
+    /// scope.
+    pub(crate) fn where_predicates_in_scope<'a>(
+        &'a self,
+    ) -> impl Iterator<Item = &'a WherePredicate> + 'a {
+        self.scopes
+            .iter()
+            .flat_map(|scope| {
+                match scope {
+                    Scope::GenericParams(gp) => Some(gp.where_predicates.iter()),
+                    _ => None,
+                }
+                .into_iter()
+            })
+            .flatten()
+    }
+
     pub(crate) fn traits_in_scope<'a>(&'a self) -> impl Iterator<Item = Trait> + 'a {
         // FIXME prelude
         self.scopes
@@ -208,6 +244,14 @@ impl Resolver {
             .flatten()
     }
 
+    /// The innermost trait whose items are in scope.
+    pub(crate) fn enclosing_trait(&self) -> Option<Trait> {
+        self.scopes.iter().rev().find_map(|scope| match scope {
+            Scope::TraitScope(t) => Some(*t),
+            _ => None,
+        })
+    }
+
     fn module(&self) -> Option<(&CrateDefMap, CrateModuleId)> {
         self.scopes.iter().rev().find_map(|scope| match scope {
             Scope::ModuleScope(m) => Some((&*m.crate_def_map, m.module_id)),
@@ -235,6 +279,10 @@ impl Resolver {
         self.push_scope(Scope::ImplBlockScope(impl_block))
     }
 
+    pub(crate) fn push_trait_scope(self, trait_: Trait) -> Resolver {
+        self.push_scope(Scope::TraitScope(trait_))
+    }
+
     pub(crate) fn push_module_scope(
         self,
         crate_def_map: Arc<CrateDefMap>,
@@ -275,6 +323,7 @@ impl Scope {
                     PerNs::none()
                 }
             }
+            Scope::TraitScope(_) => PerNs::none(),
             Scope::ExprScope(e) => {
                 let entry =
                     e.expr_scopes.entries(e.scope_id).iter().find(|entry| entry.name() == name);
@@ -317,6 +366,7 @@ impl Scope {
             Scope::ImplBlockScope(i) => {
                 f(Name::self_type(), PerNs::types(Resolution::SelfType(i.clone())));
             }
+            Scope::TraitScope(_) => {}
             Scope::ExprScope(e) => {
                 e.expr_scopes.entries(e.scope_id).iter().for_each(|e| {
                     f(e.name().clone(), PerNs::values(Resolution::LocalBinding(e.pat())));
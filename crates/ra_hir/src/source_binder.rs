@@ -12,13 +12,14 @@ use ra_db::{FileId, FilePosition};
 use ra_syntax::{
     SyntaxNode, AstPtr, TextUnit, SyntaxNodePtr, TextRange,TreeArc,
     ast::{self, AstNode, NameOwner},
-    algo::find_node_at_offset,
+    algo::{find_node_at_offset, find_token_at_offset},
     SyntaxKind::*,
 };
 
 use crate::{
     HirDatabase, Function, Struct, Enum, Const, Static, Either, DefWithBody, PerNs, Name,
-    AsName, Module, HirFileId, Crate, Trait, Resolver, Ty,Path,
+    AsName, Module, HirFileId, Crate, Trait, TypeAlias, Resolver, Ty,Path,
+    generics::{GenericDef, GenericParam},
     expr::{BodySourceMap, scope::{ScopeId, ExprScopes}},
     ids::{LocationCtx,MacroCallId},
     docs::{docs_from_ast,Documentation},
@@ -122,6 +123,79 @@ pub fn trait_from_module(
     Trait { id: ctx.to_def(trait_def) }
 }
 
+pub fn function_from_module(
+    db: &impl HirDatabase,
+    module: Module,
+    fn_def: &ast::FnDef,
+) -> Function {
+    let (file_id, _) = module.definition_source(db);
+    let file_id = file_id.into();
+    let ctx = LocationCtx::new(db, module, file_id);
+    Function { id: ctx.to_def(fn_def) }
+}
+
+/// Locates the type param declared at the given position, together with the
+/// item declaring it.
+pub fn generic_param_from_position(
+    db: &impl HirDatabase,
+    position: FilePosition,
+) -> Option<(GenericDef, GenericParam)> {
+    let file = db.parse(position.file_id);
+    let type_param = find_node_at_offset::<ast::TypeParam>(file.syntax(), position.offset)?;
+    let def = generic_def_from_child_node(db, position.file_id, type_param.syntax())?;
+    let (params, source_map) = db.generic_params_with_source_map(def);
+    let idx = source_map.param_idx(AstPtr::new(type_param))?;
+    let param = params.params_including_parent().into_iter().find(|p| p.idx == idx)?.clone();
+    Some((def, param))
+}
+
+/// Finds the innermost item with generic params, like a function or an impl
+/// block, that contains the given offset.
+pub fn find_enclosing_generic_def(
+    db: &impl HirDatabase,
+    file_id: FileId,
+    offset: TextUnit,
+) -> Option<GenericDef> {
+    let file = db.parse(file_id);
+    let token = find_token_at_offset(file.syntax(), offset).left_biased()?;
+    generic_def_from_child_node(db, file_id, token.parent())
+}
+
+fn generic_def_from_child_node(
+    db: &impl HirDatabase,
+    file_id: FileId,
+    node: &SyntaxNode,
+) -> Option<GenericDef> {
+    let module = module_from_child_node(db, file_id, node)?;
+    let ctx = LocationCtx::new(db, module, file_id.into());
+    node.ancestors().find_map(|node| {
+        if let Some(def) = ast::FnDef::cast(node) {
+            return Some(Function { id: ctx.to_def(def) }.into());
+        }
+        if let Some(def) = ast::StructDef::cast(node) {
+            return Some(Struct { id: ctx.to_def(def) }.into());
+        }
+        if let Some(def) = ast::EnumDef::cast(node) {
+            return Some(Enum { id: ctx.to_def(def) }.into());
+        }
+        if let Some(def) = ast::TraitDef::cast(node) {
+            return Some(Trait { id: ctx.to_def(def) }.into());
+        }
+        if let Some(def) = ast::TypeAliasDef::cast(node) {
+            return Some(TypeAlias { id: ctx.to_def(def) }.into());
+        }
+        if let Some(def) = ast::ImplBlock::cast(node) {
+            let range = def.syntax().range();
+            let impl_block = module.impl_blocks(db).into_iter().find(|it| {
+                let (impl_file_id, source) = it.source(db);
+                impl_file_id == HirFileId::from(file_id) && source.syntax().range() == range
+            })?;
+            return Some(impl_block.into());
+        }
+        None
+    })
+}
+
 fn try_get_resolver_for_node(
     db: &impl HirDatabase,
     file_id: FileId,
@@ -399,6 +473,21 @@ impl SourceAnalyzer {
         )
     }
 
+    /// The names of the lifetime params declared by the surrounding items.
+    pub fn lifetimes_in_scope(&self) -> Vec<Name> {
+        self.resolver.lifetimes_in_scope().cloned().collect()
+    }
+
+    /// Iterates over the possible derefs of `ty`, including those through
+    /// `Deref` impls visible from this position.
+    pub fn autoderef<'a>(
+        &'a self,
+        db: &'a impl HirDatabase,
+        ty: Ty,
+    ) -> impl Iterator<Item = Ty> + 'a {
+        ty.autoderef(db, &self.resolver)
+    }
+
     #[cfg(test)]
     pub(crate) fn body_source_map(&self) -> Arc<BodySourceMap> {
         self.body_source_map.clone().unwrap()
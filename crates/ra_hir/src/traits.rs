@@ -3,7 +3,11 @@
 use std::sync::Arc;
 use rustc_hash::FxHashMap;
 
-use ra_syntax::ast::{self, NameOwner};
+use ra_syntax::{
+    AstNode,
+    SyntaxKind::AUTO_KW,
+    ast::{self, NameOwner},
+};
 
 use crate::{Function, Const, TypeAlias, Name, DefDatabase, Trait, ids::LocationCtx, name::AsName, Module};
 
@@ -11,6 +15,7 @@ use crate::{Function, Const, TypeAlias, Name, DefDatabase, Trait, ids::LocationC
 pub struct TraitData {
     name: Option<Name>,
     items: Vec<TraitItem>,
+    auto: bool,
 }
 
 impl TraitData {
@@ -31,7 +36,8 @@ impl TraitData {
         } else {
             Vec::new()
         };
-        Arc::new(TraitData { name, items })
+        let auto = node.syntax().children_with_tokens().any(|it| it.kind() == AUTO_KW);
+        Arc::new(TraitData { name, items, auto })
     }
 
     pub(crate) fn name(&self) -> &Option<Name> {
@@ -41,6 +47,18 @@ impl TraitData {
     pub(crate) fn items(&self) -> &[TraitItem] {
         &self.items
     }
+
+    pub(crate) fn is_auto(&self) -> bool {
+        self.auto
+    }
+
+    /// The associated types of the trait, in the order of their declaration.
+    pub(crate) fn associated_types<'a>(&'a self) -> impl Iterator<Item = TypeAlias> + 'a {
+        self.items.iter().filter_map(|item| match item {
+            TraitItem::TypeAlias(it) => Some(*it),
+            _ => None,
+        })
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -16,12 +16,16 @@ use std::sync::Arc;
 use std::ops::Deref;
 use std::{fmt, mem};
 
-use crate::{Name, AdtDef, type_ref::Mutability, db::HirDatabase, Trait, GenericParams};
+use crate::{
+    Name, AdtDef, TypeAlias, type_ref::Mutability, db::HirDatabase, Trait, GenericParams,
+    generics::HasGenericParams,
+};
 use display::{HirDisplay, HirFormatter};
 
 pub(crate) use lower::{TypableDef, type_for_def, type_for_field, callable_item_sig};
-pub(crate) use infer::{infer, InferenceResult, InferTy};
+pub(crate) use infer::{infer, infer_with_mode, InferenceResult, InferTy};
 pub use lower::CallableDef;
+pub use infer::InferenceMode;
 
 /// A type constructor or type name: this might be something like the primitive
 /// type `bool`, a struct like `Vec`, or things like function pointers or
@@ -89,6 +93,24 @@ pub enum TypeCtor {
 
     /// A tuple type.  For example, `(i32, bool)`.
     Tuple { cardinality: u16 },
+
+    /// A trait object, like `dyn Iterator<Item = u32>`. The parameters are
+    /// the args of the trait (without `Self`), followed by the values of the
+    /// associated types of the trait in the order of their declaration, which
+    /// are `{unknown}` if not bound.
+    Dyn(Trait),
+
+    /// An associated type of a trait, written as `<T as Iterator>::Item`. The
+    /// parameters are the args of the trait, starting with `Self`. These are
+    /// only created for `Self::Item` inside of traits for now, and replaced
+    /// by the actual type during inference.
+    AssocType(TypeAlias),
+
+    /// The opaque type of a type alias defined as `impl Trait`, like `Foo` in
+    /// `type Foo = impl Iterator<Item = u32>;`. All that is known about it is
+    /// that it implements the bounds. The parameters are the args of the
+    /// alias.
+    Opaque(TypeAlias),
 }
 
 /// A nominal type with (maybe 0) type parameters. This might be a primitive
@@ -221,6 +243,10 @@ pub struct TraitRef {
 }
 
 impl TraitRef {
+    pub fn trait_(&self) -> Trait {
+        self.trait_
+    }
+
     pub fn self_ty(&self) -> &Ty {
         &self.substs[0]
     }
@@ -393,6 +419,29 @@ impl Ty {
         }
     }
 
+    pub(crate) fn as_dyn_trait(&self) -> Option<Trait> {
+        match self {
+            Ty::Apply(ApplicationTy { ctor: TypeCtor::Dyn(trait_), .. }) => Some(*trait_),
+            _ => None,
+        }
+    }
+
+    /// For a trait object type, returns the value of the associated type
+    /// `name` of its trait, if it is bound.
+    pub(crate) fn dyn_assoc_type(&self, db: &impl HirDatabase, name: &Name) -> Option<Ty> {
+        let trait_ = self.as_dyn_trait()?;
+        let parameters = self.substs()?;
+        let num_args = trait_.generic_params(db).count_params_including_parent() - 1;
+        let idx = trait_
+            .trait_data(db)
+            .associated_types()
+            .position(|it| it.name(db).as_ref() == Some(name))?;
+        match parameters.get(num_args + idx)? {
+            Ty::Unknown => None,
+            ty => Some(ty.clone()),
+        }
+    }
+
     /// If this is a type with type parameters (an ADT or function), replaces
     /// the `Substs` for these type parameters with the given ones. (So e.g. if
     /// `self` is `Option<_>` and the substs contain `u32`, we'll have
@@ -517,6 +566,44 @@ impl HirDisplay for ApplicationTy {
                     write!(f, ">")?;
                 }
             }
+            TypeCtor::Dyn(trait_) => {
+                let name = trait_.name(f.db).unwrap_or_else(Name::missing);
+                write!(f, "dyn {}", name)?;
+                let num_args = trait_.generic_params(f.db).count_params_including_parent() - 1;
+                let (args, assoc_tys) = self.parameters.split_at(num_args);
+                let mut parts: Vec<String> =
+                    args.iter().map(|ty| ty.display(f.db).to_string()).collect();
+                for (assoc, ty) in trait_.trait_data(f.db).associated_types().zip(assoc_tys) {
+                    if *ty != Ty::Unknown {
+                        let assoc_name = assoc.name(f.db).unwrap_or_else(Name::missing);
+                        parts.push(format!("{} = {}", assoc_name, ty.display(f.db)));
+                    }
+                }
+                if !parts.is_empty() {
+                    write!(f, "<{}>", parts.join(", "))?;
+                }
+            }
+            TypeCtor::AssocType(type_alias) => {
+                let trait_name = type_alias
+                    .parent_trait(f.db)
+                    .and_then(|it| it.name(f.db))
+                    .unwrap_or_else(Name::missing);
+                let name = type_alias.name(f.db).unwrap_or_else(Name::missing);
+                write!(f, "<{} as {}", self.parameters[0].display(f.db), trait_name)?;
+                if self.parameters.len() > 1 {
+                    write!(f, "<")?;
+                    f.write_joined(&self.parameters[1..], ", ")?;
+                    write!(f, ">")?;
+                }
+                write!(f, ">::{}", name)?;
+            }
+            TypeCtor::Opaque(type_alias) => {
+                let bounds: Vec<String> = lower::opaque_type_bounds(f.db, type_alias)
+                    .into_iter()
+                    .map(|it| it.name(f.db).unwrap_or_else(Name::missing).to_string())
+                    .collect();
+                write!(f, "impl {}", bounds.join(" + "))?;
+            }
         }
         Ok(())
     }
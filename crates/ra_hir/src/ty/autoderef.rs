@@ -5,17 +5,33 @@
 
 use std::iter::successors;
 
-use crate::HirDatabase;
-use super::Ty;
+use crate::{HirDatabase, Crate, Name, lang_item::LangItemTarget, resolve::Resolver};
+use super::{Ty, traits::impl_assoc_type};
+
+const AUTODEREF_RECURSION_LIMIT: usize = 10;
 
 impl Ty {
     /// Iterates over the possible derefs of `ty`.
-    pub fn autoderef<'a>(self, db: &'a impl HirDatabase) -> impl Iterator<Item = Ty> + 'a {
-        successors(Some(self), move |ty| ty.autoderef_step(db))
+    pub(crate) fn autoderef<'a>(
+        self,
+        db: &'a impl HirDatabase,
+        resolver: &'a Resolver,
+    ) -> impl Iterator<Item = Ty> + 'a {
+        successors(Some(self), move |ty| ty.autoderef_step(db, resolver))
+            .take(AUTODEREF_RECURSION_LIMIT)
+    }
+
+    fn autoderef_step(&self, db: &impl HirDatabase, resolver: &Resolver) -> Option<Ty> {
+        self.builtin_deref().or_else(|| self.deref_by_trait(db, resolver.krate()?))
     }
 
-    fn autoderef_step(&self, _db: &impl HirDatabase) -> Option<Ty> {
-        // FIXME Deref::deref
-        self.builtin_deref()
+    /// Derefs through an impl of the `Deref` lang item trait, by finding an
+    /// impl whose self type matches and returning its `Target`.
+    fn deref_by_trait(&self, db: &impl HirDatabase, krate: Crate) -> Option<Ty> {
+        let deref_trait = match db.lang_item(krate, "deref".into())? {
+            LangItemTarget::Trait(t) => t,
+            _ => return None,
+        };
+        impl_assoc_type(db, krate, deref_trait, self, &Name::target())
     }
 }
@@ -26,7 +26,7 @@ use ra_arena::map::ArenaMap;
 use test_utils::tested_by;
 
 use crate::{
-    Function, StructField, Path, Name,
+    Function, StructField, Path, Name, Trait,
     FnSignature, AdtDef,ConstSignature,
     HirDatabase,
     DefWithBody,
@@ -34,17 +34,19 @@ use crate::{
     type_ref::{TypeRef, Mutability},
     expr::{Body, Expr, BindingAnnotation, Literal, ExprId, Pat, PatId, UnaryOp, BinaryOp, Statement, FieldPat,Array, self},
     generics::{GenericParams, HasGenericParams},
-    path::{GenericArgs, GenericArg},
+    path::{GenericArgs, GenericArg, PathKind},
     ModuleDef,
     adt::VariantDef,
     resolve::{Resolver, Resolution},
     nameres::Namespace,
+    lang_item::LangItemTarget,
     ty::infer::diagnostics::InferenceDiagnostic,
     diagnostics::DiagnosticSink,
 };
 use super::{
-    Ty, TypableDef, Substs, primitive, op, ApplicationTy, TypeCtor, CallableDef, TraitRef,
-    traits::{Solution, Obligation, Guidance},
+    Ty, TypableDef, Substs, primitive, op, ApplicationTy, TypeCtor, CallableDef, TraitRef, FnSig,
+    traits::{Solution, Obligation, Guidance, impl_assoc_type},
+    lower::{param_assoc_type_path, param_assoc_type_candidates, missing_assoc_type_bindings},
     method_resolution,
 };
 
@@ -52,10 +54,28 @@ mod unify;
 
 /// The entry point of type inference.
 pub fn infer(db: &impl HirDatabase, def: DefWithBody) -> Arc<InferenceResult> {
+    infer_with_mode(db, def, InferenceMode::Full)
+}
+
+/// How thoroughly associated type projections are solved.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum InferenceMode {
+    Full,
+    /// Projections through the bounds of type params, like `I::Item` for
+    /// `I: IntoIterator<Item = u8>`, are left unknown. This is meant for a
+    /// quick first pass over large files, which is refined by a full pass.
+    Fast,
+}
+
+pub(crate) fn infer_with_mode(
+    db: &impl HirDatabase,
+    def: DefWithBody,
+    mode: InferenceMode,
+) -> Arc<InferenceResult> {
     db.check_canceled();
     let body = def.body(db);
     let resolver = def.resolver(db);
-    let mut ctx = InferenceContext::new(db, body, resolver);
+    let mut ctx = InferenceContext::new(db, body, resolver, mode);
 
     match def {
         DefWithBody::Const(ref c) => ctx.collect_const_signature(&c.signature(db)),
@@ -159,6 +179,7 @@ struct InferenceContext<'a, D: HirDatabase> {
     db: &'a D,
     body: Arc<Body>,
     resolver: Resolver,
+    mode: InferenceMode,
     var_unification_table: InPlaceUnificationTable<TypeVarId>,
     obligations: Vec<Obligation>,
     method_resolutions: FxHashMap<ExprId, Function>,
@@ -167,12 +188,15 @@ struct InferenceContext<'a, D: HirDatabase> {
     type_of_expr: ArenaMap<ExprId, Ty>,
     type_of_pat: ArenaMap<PatId, Ty>,
     diagnostics: Vec<InferenceDiagnostic>,
+    /// The signatures expected for closures passed as args, from the `Fn`
+    /// bounds of the params they are passed for.
+    closure_sigs: FxHashMap<ExprId, FnSig>,
     /// The return type of the function being inferred.
     return_ty: Ty,
 }
 
 impl<'a, D: HirDatabase> InferenceContext<'a, D> {
-    fn new(db: &'a D, body: Arc<Body>, resolver: Resolver) -> Self {
+    fn new(db: &'a D, body: Arc<Body>, resolver: Resolver, mode: InferenceMode) -> Self {
         InferenceContext {
             method_resolutions: FxHashMap::default(),
             field_resolutions: FxHashMap::default(),
@@ -180,12 +204,14 @@ impl<'a, D: HirDatabase> InferenceContext<'a, D> {
             type_of_expr: ArenaMap::default(),
             type_of_pat: ArenaMap::default(),
             diagnostics: Vec::default(),
+            closure_sigs: FxHashMap::default(),
             var_unification_table: InPlaceUnificationTable::new(),
             obligations: Vec::default(),
             return_ty: Ty::Unknown, // set in collect_fn_signature
             db,
             body,
             resolver,
+            mode,
         }
     }
 
@@ -318,9 +344,53 @@ impl<'a, D: HirDatabase> InferenceContext<'a, D> {
     }
 
     fn insert_type_vars(&mut self, ty: Ty) -> Ty {
+        let ty = self.normalize_assoc_types(ty);
         ty.fold(&mut |ty| self.insert_type_vars_shallow(ty))
     }
 
+    /// Replaces associated types like `<S as Iterator>::Item` by the type
+    /// they stand for, or by a type var if it isn't known.
+    fn normalize_assoc_types(&mut self, ty: Ty) -> Ty {
+        ty.fold(&mut |ty| match ty {
+            Ty::Apply(ApplicationTy { ctor: TypeCtor::AssocType(type_alias), parameters }) => {
+                let trait_ = type_alias.parent_trait(self.db);
+                let name = type_alias.name(self.db);
+                let projected = match (trait_, name) {
+                    (Some(trait_), Some(name)) => {
+                        self.project_assoc_type(parameters[0].clone(), trait_, &name)
+                    }
+                    _ => None,
+                };
+                projected.unwrap_or_else(|| self.new_type_var())
+            }
+            ty => ty,
+        })
+    }
+
+    /// The signature of a callable type, with its associated types normalized.
+    fn callable_sig(&mut self, ty: &Ty) -> Option<FnSig> {
+        let sig = ty.callable_sig(self.db).or_else(|| fn_bound_sig(self.db, &self.resolver, ty))?;
+        let params_and_return: Vec<Ty> =
+            sig.params_and_return.iter().map(|ty| self.normalize_assoc_types(ty.clone())).collect();
+        Some(FnSig { params_and_return: params_and_return.into() })
+    }
+
+    /// Records the signatures expected for the closures among the `args` of a
+    /// call of `func`, from the `Fn` bounds of the params they are passed
+    /// for, like `(i32) -> i32` for `|x| x + 1` in `apply(|x| x + 1)` with
+    /// `fn apply<F: Fn(i32) -> i32>(f: F)`.
+    fn expect_closure_sigs(&mut self, func: Function, substs: &Substs, args: &[ExprId]) {
+        let sig = self.db.callable_item_signature(CallableDef::Function(func));
+        let resolver = func.resolver(self.db);
+        for (arg, param) in args.iter().zip(sig.params()) {
+            if let Expr::Lambda { .. } = self.body[*arg] {
+                if let Some(closure_sig) = fn_bound_sig(self.db, &resolver, param) {
+                    self.closure_sigs.insert(*arg, closure_sig.subst(substs));
+                }
+            }
+        }
+    }
+
     fn resolve_obligations_as_possible(&mut self) {
         let obligations = mem::replace(&mut self.obligations, Vec::new());
         for obligation in obligations {
@@ -494,10 +564,9 @@ impl<'a, D: HirDatabase> InferenceContext<'a, D> {
                     // FIXME associated item of generic param
                     return None;
                 }
-                Resolution::SelfType(_) => {
-                    // FIXME associated item of self type
-                    return None;
-                }
+                // the self type of an impl has the impl's params, which the
+                // items of the impl are substituted with as well
+                Resolution::SelfType(impl_block) => impl_block.target_ty(self.db),
             };
 
             // Attempt to find an impl_item for the type which has a name matching
@@ -877,6 +946,7 @@ impl<'a, D: HirDatabase> InferenceContext<'a, D> {
         args: &[ExprId],
         method_name: &Name,
         generic_args: Option<&GenericArgs>,
+        expected: &Expectation,
     ) -> Ty {
         let receiver_ty = self.infer_expr(receiver, &Expectation::none());
         let canonicalized_receiver = self.canonicalizer().canonicalize_ty(receiver_ty.clone());
@@ -903,7 +973,7 @@ impl<'a, D: HirDatabase> InferenceContext<'a, D> {
         let method_ty = method_ty.apply_substs(substs);
         let method_ty = self.insert_type_vars(method_ty);
         self.register_obligations_for_call(&method_ty);
-        let (expected_receiver_ty, param_tys, ret_ty) = match method_ty.callable_sig(self.db) {
+        let (expected_receiver_ty, param_tys, ret_ty) = match self.callable_sig(&method_ty) {
             Some(sig) => {
                 if !sig.params().is_empty() {
                     (sig.params()[0].clone(), sig.params()[1..].to_vec(), sig.ret().clone())
@@ -920,6 +990,12 @@ impl<'a, D: HirDatabase> InferenceContext<'a, D> {
             _ => derefed_receiver_ty,
         };
         self.unify(&expected_receiver_ty, &actual_receiver_ty);
+        // the expected type can select the instantiation of a generic trait,
+        // like `Into<String>` for `let x: String = s.into();`, before the args
+        // are inferred
+        if expected.ty != Ty::Unknown {
+            self.unify(&ret_ty, &expected.ty);
+        }
 
         let param_iter = param_tys.into_iter().chain(repeat(Ty::Unknown));
         for (arg, param) in args.iter().zip(param_iter) {
@@ -928,6 +1004,114 @@ impl<'a, D: HirDatabase> InferenceContext<'a, D> {
         ret_ty
     }
 
+    /// The type of the items of `iterable_ty` when iterating over it in a `for`
+    /// loop, i.e. `<iterable_ty as IntoIterator>::Item`.
+    fn infer_into_iter_item(&mut self, iterable_ty: Ty) -> Option<Ty> {
+        let into_iter_trait = self.resolve_std_trait(Name::into_iterator_path())?;
+        self.project_assoc_type(iterable_ty, into_iter_trait, &Name::item())
+    }
+
+    /// The type of `expr?`, i.e. `<inner_ty as Try>::Ok`. Like in rustc, the
+    /// error of `expr` has to be converted into the error of the return type
+    /// by a single `From` conversion; if there is none, this is diagnosed.
+    fn infer_try(&mut self, tgt_expr: ExprId, inner_ty: Ty) -> Option<Ty> {
+        let try_trait = self.resolve_std_trait(Name::try_path())?;
+        let ok_ty = self.project_assoc_type(inner_ty.clone(), try_trait, &Name::ok());
+        let error_ty = self.project_assoc_type(inner_ty, try_trait, &Name::error());
+        let return_ty = self.return_ty.clone();
+        let return_error_ty = self.project_assoc_type(return_ty, try_trait, &Name::error());
+        if let (Some(from), Some(to)) = (error_ty, return_error_ty) {
+            let from = self.resolve_ty_completely(&mut vec![], from);
+            let to = self.resolve_ty_completely(&mut vec![], to);
+            if !self.is_from_convertible(from.clone(), to.clone()) {
+                self.diagnostics.push(InferenceDiagnostic::QuestionMarkErrorConversion {
+                    expr: tgt_expr,
+                    from,
+                    to,
+                });
+            }
+        }
+        ok_ty
+    }
+
+    /// Whether `from` is the same type as `to` or there is an impl of
+    /// `From<from>` for `to`, for completely resolved types. Unknown types are
+    /// assumed to be convertible, so that they don't produce spurious
+    /// diagnostics.
+    fn is_from_convertible(&mut self, from: Ty, to: Ty) -> bool {
+        if from == to || from == Ty::Unknown || to == Ty::Unknown {
+            return true;
+        }
+        let (from_trait, krate) =
+            match (self.resolve_std_trait(Name::from_path()), self.resolver.krate()) {
+                (Some(from_trait), Some(krate)) => (from_trait, krate),
+                _ => return true,
+            };
+        let trait_ref = TraitRef { trait_: from_trait, substs: vec![to, from].into() };
+        let canonicalized = self.canonicalizer().canonicalize_trait_ref(trait_ref);
+        super::traits::implements(self.db, krate, canonicalized.value).is_some()
+    }
+
+    /// Resolves the absolute path given by `segments` to a trait, like
+    /// `std::iter::IntoIterator`.
+    fn resolve_std_trait(&self, segments: Vec<Name>) -> Option<Trait> {
+        let path = Path::from_simple_segments(PathKind::Abs, segments);
+        match self.resolver.resolve_path(self.db, &path).take_types() {
+            Some(Resolution::Def(ModuleDef::Trait(t))) => Some(t),
+            _ => None,
+        }
+    }
+
+    /// The result type of a binary operator that is implemented by a trait,
+    /// i.e. `<lhs_ty as Add>::Output` for `+`.
+    // FIXME: the `Rhs` param of the operator traits is ignored
+    fn infer_binary_op_output(&mut self, op: BinaryOp, lhs_ty: Ty) -> Option<Ty> {
+        let krate = self.resolver.krate()?;
+        let op_trait = match self.db.lang_item(krate, op::binary_op_lang_item(op)?.into())? {
+            LangItemTarget::Trait(t) => t,
+            _ => return None,
+        };
+        self.project_assoc_type(lhs_ty, op_trait, &Name::output())
+    }
+
+    /// Finds the value of the associated type `name` of `trait_` for `ty`.
+    fn project_assoc_type(&mut self, ty: Ty, trait_: Trait, name: &Name) -> Option<Ty> {
+        match self.resolve_ty_as_possible(&mut vec![], ty) {
+            // for type params, the value comes from a binding in a bound like
+            // `I: IntoIterator<Item = T>`
+            Ty::Param { .. } if self.mode == InferenceMode::Fast => None,
+            Ty::Param { name: param_name, .. } => {
+                let param_type_ref = TypeRef::Path(param_name.into());
+                let type_ref = self.resolver.where_predicates_in_scope().find_map(|pred| {
+                    if pred.type_ref != param_type_ref {
+                        return None;
+                    }
+                    match self.resolver.resolve_path(self.db, &pred.trait_ref).take_types() {
+                        Some(Resolution::Def(ModuleDef::Trait(t))) if t == trait_ => {}
+                        _ => return None,
+                    }
+                    let args = pred.trait_ref.segments.last()?.args_and_bindings.as_ref()?;
+                    args.bindings.iter().find(|(n, _)| n == name).map(|(_, t)| t)
+                })?;
+                let type_ref = type_ref.clone();
+                Some(self.make_ty(&type_ref))
+            }
+            // trait objects carry the values of the associated types of their
+            // trait, as in `dyn Iterator<Item = u32>`
+            ty @ Ty::Apply(ApplicationTy { ctor: TypeCtor::Dyn(_), .. }) => {
+                if ty.as_dyn_trait() == Some(trait_) {
+                    ty.dyn_assoc_type(self.db, name)
+                } else {
+                    None
+                }
+            }
+            ty => {
+                let krate = self.resolver.krate()?;
+                impl_assoc_type(self.db, krate, trait_, &ty, name)
+            }
+        }
+    }
+
     fn infer_expr(&mut self, tgt_expr: ExprId, expected: &Expectation) -> Ty {
         let body = Arc::clone(&self.body); // avoid borrow checker problem
         let ty = match &body[tgt_expr] {
@@ -960,31 +1144,40 @@ impl<'a, D: HirDatabase> InferenceContext<'a, D> {
                 Ty::unit()
             }
             Expr::For { iterable, body, pat } => {
-                let _iterable_ty = self.infer_expr(*iterable, &Expectation::none());
-                self.infer_pat(*pat, &Ty::Unknown, BindingMode::default());
+                let iterable_ty = self.infer_expr(*iterable, &Expectation::none());
+                let pat_ty = self.infer_into_iter_item(iterable_ty).unwrap_or(Ty::Unknown);
+                self.infer_pat(*pat, &pat_ty, BindingMode::default());
                 self.infer_expr(*body, &Expectation::has_type(Ty::unit()));
                 Ty::unit()
             }
             Expr::Lambda { body, args, arg_types } => {
                 assert_eq!(args.len(), arg_types.len());
 
-                for (arg_pat, arg_type) in args.iter().zip(arg_types.iter()) {
+                let closure_sig = self.closure_sigs.remove(&tgt_expr);
+                let expected_params = closure_sig
+                    .iter()
+                    .flat_map(|it| it.params().iter().cloned())
+                    .chain(repeat(Ty::Unknown));
+                for ((arg_pat, arg_type), expected_param) in
+                    args.iter().zip(arg_types.iter()).zip(expected_params)
+                {
                     let expected = if let Some(type_ref) = arg_type {
                         let ty = self.make_ty(type_ref);
                         ty
                     } else {
-                        Ty::Unknown
+                        expected_param
                     };
                     self.infer_pat(*arg_pat, &expected, BindingMode::default());
                 }
 
                 // FIXME: infer lambda type etc.
-                let _body_ty = self.infer_expr(*body, &Expectation::none());
+                let expected_ret = closure_sig.as_ref().map_or(Ty::Unknown, |it| it.ret().clone());
+                let _body_ty = self.infer_expr(*body, &Expectation::has_type(expected_ret));
                 Ty::Unknown
             }
             Expr::Call { callee, args } => {
                 let callee_ty = self.infer_expr(*callee, &Expectation::none());
-                let (param_tys, ret_ty) = match callee_ty.callable_sig(self.db) {
+                let (param_tys, ret_ty) = match self.callable_sig(&callee_ty) {
                     Some(sig) => (sig.params().to_vec(), sig.ret().clone()),
                     None => {
                         // Not callable
@@ -992,6 +1185,13 @@ impl<'a, D: HirDatabase> InferenceContext<'a, D> {
                         (Vec::new(), Ty::Unknown)
                     }
                 };
+                if let Ty::Apply(ApplicationTy {
+                    ctor: TypeCtor::FnDef(CallableDef::Function(func)),
+                    parameters,
+                }) = &callee_ty
+                {
+                    self.expect_closure_sigs(*func, parameters, args);
+                }
                 // FIXME register obligations from where clauses from the function
                 let param_iter = param_tys.into_iter().chain(repeat(Ty::Unknown));
                 for (arg, param) in args.iter().zip(param_iter) {
@@ -1000,7 +1200,14 @@ impl<'a, D: HirDatabase> InferenceContext<'a, D> {
                 ret_ty
             }
             Expr::MethodCall { receiver, args, method_name, generic_args } => self
-                .infer_method_call(tgt_expr, *receiver, &args, &method_name, generic_args.as_ref()),
+                .infer_method_call(
+                    tgt_expr,
+                    *receiver,
+                    &args,
+                    &method_name,
+                    generic_args.as_ref(),
+                    expected,
+                ),
             Expr::Match { expr, arms } => {
                 let expected = if expected.ty == Ty::Unknown {
                     Expectation::has_type(self.new_type_var())
@@ -1045,6 +1252,12 @@ impl<'a, D: HirDatabase> InferenceContext<'a, D> {
             }
             Expr::StructLit { path, fields, spread } => {
                 let (ty, def_id) = self.resolve_variant(path.as_ref());
+                // the base of a struct update has the same type, so inferring
+                // it first provides the generic args for the fields
+                if let Some(expr) = spread {
+                    self.infer_expr(*expr, &Expectation::has_type(ty.clone()));
+                }
+                let ty = self.resolve_ty_as_possible(&mut vec![], ty);
                 let substs = ty.substs().unwrap_or_else(Substs::empty);
                 for (field_idx, field) in fields.into_iter().enumerate() {
                     let field_ty = def_id
@@ -1062,15 +1275,12 @@ impl<'a, D: HirDatabase> InferenceContext<'a, D> {
                         .subst(&substs);
                     self.infer_expr(field.expr, &Expectation::has_type(field_ty));
                 }
-                if let Some(expr) = spread {
-                    self.infer_expr(*expr, &Expectation::has_type(ty.clone()));
-                }
                 ty
             }
             Expr::Field { expr, name } => {
                 let receiver_ty = self.infer_expr(*expr, &Expectation::none());
                 let ty = receiver_ty
-                    .autoderef(self.db)
+                    .autoderef(self.db, &self.resolver.clone())
                     .find_map(|derefed_ty| match derefed_ty {
                         Ty::Apply(a_ty) => match a_ty.ctor {
                             TypeCtor::Tuple { .. } => {
@@ -1091,8 +1301,8 @@ impl<'a, D: HirDatabase> InferenceContext<'a, D> {
                 self.insert_type_vars(ty)
             }
             Expr::Try { expr } => {
-                let _inner_ty = self.infer_expr(*expr, &Expectation::none());
-                Ty::Unknown
+                let inner_ty = self.infer_expr(*expr, &Expectation::none());
+                self.infer_try(tgt_expr, inner_ty).unwrap_or(Ty::Unknown)
             }
             Expr::Cast { expr, type_ref } => {
                 let _inner_ty = self.infer_expr(*expr, &Expectation::none());
@@ -1168,13 +1378,16 @@ impl<'a, D: HirDatabase> InferenceContext<'a, D> {
                         _ => Expectation::none(),
                     };
                     let lhs_ty = self.infer_expr(*lhs, &lhs_expectation);
-                    // FIXME: find implementation of trait corresponding to operation
-                    // symbol and resolve associated `Output` type
-                    let rhs_expectation = op::binary_op_rhs_expectation(*op, lhs_ty);
+                    let rhs_expectation = op::binary_op_rhs_expectation(*op, lhs_ty.clone());
                     let rhs_ty = self.infer_expr(*rhs, &Expectation::has_type(rhs_expectation));
 
-                    // FIXME: similar as above, return ty is often associated trait type
-                    op::binary_op_return_ty(*op, rhs_ty)
+                    match op::binary_op_return_ty(*op, rhs_ty) {
+                        // not a builtin operation, so look for the operator trait
+                        Ty::Unknown => {
+                            self.infer_binary_op_output(*op, lhs_ty).unwrap_or(Ty::Unknown)
+                        }
+                        ty => ty,
+                    }
                 }
                 _ => Ty::Unknown,
             },
@@ -1253,12 +1466,16 @@ impl<'a, D: HirDatabase> InferenceContext<'a, D> {
         for stmt in statements {
             match stmt {
                 Statement::Let { pat, type_ref, initializer } => {
+                    if let Some(type_ref) = type_ref {
+                        self.check_type_annotation(type_ref, *pat);
+                    }
                     let decl_ty =
                         type_ref.as_ref().map(|tr| self.make_ty(tr)).unwrap_or(Ty::Unknown);
                     let decl_ty = self.insert_type_vars(decl_ty);
                     let ty = if let Some(expr) = initializer {
-                        let expr_ty = self.infer_expr(*expr, &Expectation::has_type(decl_ty));
-                        expr_ty
+                        let expr_ty =
+                            self.infer_expr(*expr, &Expectation::has_type(decl_ty.clone()));
+                        self.coerce_fn_item(expr_ty, &decl_ty)
                     } else {
                         decl_ty
                     };
@@ -1274,6 +1491,35 @@ impl<'a, D: HirDatabase> InferenceContext<'a, D> {
         ty
     }
 
+    /// Coerces `from_ty` to `to_ty` if it is a fn item and `to_ty` is a fn
+    /// pointer with the same signature, like in `let f: fn(u8) = foo::<u8>;`.
+    /// Returns the resulting type, i.e. `from_ty` if there is no coercion.
+    // FIXME: handle the other coercions, and the other coercion sites
+    fn coerce_fn_item(&mut self, from_ty: Ty, to_ty: &Ty) -> Ty {
+        let to_ty = self.resolve_ty_shallow(to_ty).into_owned();
+        match (&from_ty, &to_ty) {
+            (
+                Ty::Apply(ApplicationTy { ctor: TypeCtor::FnDef(_), .. }),
+                Ty::Apply(ApplicationTy { ctor: TypeCtor::FnPtr { .. }, .. }),
+            ) => {
+                let sig = match self.callable_sig(&from_ty) {
+                    Some(sig) => sig,
+                    None => return from_ty,
+                };
+                let ptr_ty = Ty::apply(
+                    TypeCtor::FnPtr { num_args: sig.params().len() as u16 },
+                    Substs(sig.params_and_return),
+                );
+                if self.unify(&ptr_ty, &to_ty) {
+                    self.resolve_ty_as_possible(&mut vec![], to_ty)
+                } else {
+                    from_ty
+                }
+            }
+            _ => from_ty,
+        }
+    }
+
     fn collect_const_signature(&mut self, signature: &ConstSignature) {
         self.return_ty = self.make_ty(signature.type_ref());
     }
@@ -1281,6 +1527,7 @@ impl<'a, D: HirDatabase> InferenceContext<'a, D> {
     fn collect_fn_signature(&mut self, signature: &FnSignature) {
         let body = Arc::clone(&self.body); // avoid borrow checker problem
         for (type_ref, pat) in signature.params().iter().zip(body.params()) {
+            self.check_type_annotation(type_ref, *pat);
             let ty = self.make_ty(type_ref);
 
             self.infer_pat(*pat, &ty, BindingMode::default());
@@ -1288,6 +1535,33 @@ impl<'a, D: HirDatabase> InferenceContext<'a, D> {
         self.return_ty = self.make_ty(signature.ret_type());
     }
 
+    /// Reports the errors in the associated types of the type annotation of
+    /// `pat`: paths like `T::Item` for which several bounds of `T` declare an
+    /// associated type of that name, and trait objects like `dyn Iterator`
+    /// that leave an associated type of their trait unspecified.
+    fn check_type_annotation(&mut self, type_ref: &TypeRef, pat: PatId) {
+        let (db, resolver) = (self.db, &self.resolver);
+        let mut diagnostics = Vec::new();
+        type_ref.walk(&mut |type_ref| match type_ref {
+            TypeRef::Path(path) => {
+                if let Some((param, name)) = param_assoc_type_path(db, resolver, path) {
+                    if param_assoc_type_candidates(db, resolver, &param, name).len() > 1 {
+                        let name = name.clone();
+                        let diagnostic = InferenceDiagnostic::AmbiguousAssociatedType { pat, name };
+                        diagnostics.push(diagnostic);
+                    }
+                }
+            }
+            TypeRef::DynTrait(paths) => {
+                for name in missing_assoc_type_bindings(db, resolver, paths) {
+                    diagnostics.push(InferenceDiagnostic::MissingAssocTypeBinding { pat, name });
+                }
+            }
+            _ => {}
+        });
+        self.diagnostics.extend(diagnostics);
+    }
+
     fn infer_body(&mut self) {
         self.infer_expr(self.body.body_expr(), &Expectation::has_type(self.return_ty.clone()));
     }
@@ -1351,6 +1625,37 @@ impl UnifyValue for TypeVarValue {
     }
 }
 
+/// The signature of the `Fn`, `FnMut` or `FnOnce` bound of the type param
+/// `ty` in the scope of `resolver`, like `(u8) -> i32` for `F: Fn(u8) -> i32`.
+fn fn_bound_sig(db: &impl HirDatabase, resolver: &Resolver, ty: &Ty) -> Option<FnSig> {
+    match ty {
+        Ty::Param { .. } => {}
+        _ => return None,
+    }
+    // FIXME: use the lang items of the `Fn` traits, once we have them
+    let pred = resolver.where_predicates_in_scope().find(|pred| {
+        let is_fn_trait =
+            pred.trait_ref.segments.last().map_or(false, |it| {
+                ["Fn", "FnMut", "FnOnce"].contains(&it.name.to_string().as_str())
+            });
+        is_fn_trait && Ty::from_hir(db, resolver, &pred.type_ref) == *ty
+    })?;
+    let args = pred.trait_ref.segments.last()?.args_and_bindings.as_ref()?;
+    let mut params_and_return: Vec<Ty> = match args.args.first()? {
+        GenericArg::Type(TypeRef::Tuple(params)) => {
+            params.iter().map(|it| Ty::from_hir(db, resolver, it)).collect()
+        }
+        _ => return None,
+    };
+    let ret = args
+        .bindings
+        .iter()
+        .find(|(name, _)| *name == Name::output())
+        .map_or(Ty::Unknown, |(_, it)| Ty::from_hir(db, resolver, it));
+    params_and_return.push(ret);
+    Some(FnSig { params_and_return: params_and_return.into() })
+}
+
 /// The kinds of placeholders we need during type inference. There's separate
 /// values for general types, and for integer and float variables. The latter
 /// two are used for inference of literal values (e.g. `100` could be one of
@@ -1406,11 +1711,22 @@ impl Expectation {
 }
 
 mod diagnostics {
-    use crate::{expr::ExprId, diagnostics::{DiagnosticSink, NoSuchField}, HirDatabase, Function};
+    use crate::{
+        expr::{ExprId, PatId},
+        diagnostics::{
+            DiagnosticSink, NoSuchField, QuestionMarkErrorConversion, AmbiguousAssociatedType,
+            MissingAssocTypeBinding,
+        },
+        ty::{Ty, display::HirDisplay},
+        HirDatabase, Function, Name,
+    };
 
     #[derive(Debug, PartialEq, Eq, Clone)]
     pub(super) enum InferenceDiagnostic {
         NoSuchField { expr: ExprId, field: usize },
+        QuestionMarkErrorConversion { expr: ExprId, from: Ty, to: Ty },
+        AmbiguousAssociatedType { pat: PatId, name: Name },
+        MissingAssocTypeBinding { pat: PatId, name: Name },
     }
 
     impl InferenceDiagnostic {
@@ -1426,6 +1742,29 @@ mod diagnostics {
                     let field = owner.body_source_map(db).field_syntax(*expr, *field);
                     sink.push(NoSuchField { file, field })
                 }
+                InferenceDiagnostic::QuestionMarkErrorConversion { expr, from, to } => {
+                    let (file, _) = owner.source(db);
+                    if let Some(expr) = owner.body_source_map(db).expr_syntax(*expr) {
+                        // params are printed with their names, like `Vec<T>`
+                        let from = from.display(db).to_string();
+                        let to = to.display(db).to_string();
+                        sink.push(QuestionMarkErrorConversion { file, expr, from, to })
+                    }
+                }
+                InferenceDiagnostic::AmbiguousAssociatedType { pat, name } => {
+                    let (file, _) = owner.source(db);
+                    if let Some(pat) = owner.body_source_map(db).pat_syntax(*pat) {
+                        let pat = pat.either(|it| it.syntax_node_ptr(), |it| it.syntax_node_ptr());
+                        sink.push(AmbiguousAssociatedType { file, pat, name: name.clone() })
+                    }
+                }
+                InferenceDiagnostic::MissingAssocTypeBinding { pat, name } => {
+                    let (file, _) = owner.source(db);
+                    if let Some(pat) = owner.body_source_map(db).pat_syntax(*pat) {
+                        let pat = pat.either(|it| it.syntax_node_ptr(), |it| it.syntax_node_ptr());
+                        sink.push(MissingAssocTypeBinding { file, pat, name: name.clone() })
+                    }
+                }
             }
         }
     }
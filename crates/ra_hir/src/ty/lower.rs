@@ -8,8 +8,8 @@
 use std::iter;
 
 use crate::{
-    Function, Struct, StructField, Enum, EnumVariant, Path,
-    ModuleDef, TypeAlias,
+    Function, Struct, StructField, Enum, EnumVariant, Path, PathKind,
+    ModuleDef, TypeAlias, ImplItem, Name,
     Const, Static,
     HirDatabase,
     type_ref::TypeRef,
@@ -17,7 +17,7 @@ use crate::{
     nameres::Namespace,
     resolve::{Resolver, Resolution},
     path::{PathSegment, GenericArg},
-    generics::{GenericParams, HasGenericParams},
+    generics::{GenericParams, HasGenericParams, GenericDef},
     adt::VariantDef, Trait
 };
 use super::{Ty, primitive, FnSig, Substs, TypeCtor, TraitRef};
@@ -39,7 +39,7 @@ impl Ty {
                 let inner_ty = Ty::from_hir(db, resolver, inner);
                 Ty::apply_one(TypeCtor::RawPtr(*mutability), inner_ty)
             }
-            TypeRef::Array(inner) => {
+            TypeRef::Array(inner, _) => {
                 let inner_ty = Ty::from_hir(db, resolver, inner);
                 Ty::apply_one(TypeCtor::Array, inner_ty)
             }
@@ -58,6 +58,10 @@ impl Ty {
                 let sig = Substs(inner_tys.into());
                 Ty::apply(TypeCtor::FnPtr { num_args: sig.len() as u16 - 1 }, sig)
             }
+            TypeRef::DynTrait(paths) => Ty::from_hir_dyn_trait(db, resolver, paths),
+            // only supported as the body of a type alias for now, see
+            // `type_for_type_alias`
+            TypeRef::ImplTrait(_) => Ty::Unknown,
             TypeRef::Error => Ty::Unknown,
         }
     }
@@ -79,6 +83,13 @@ impl Ty {
             }
         }
 
+        if let Some(ty) = Ty::from_self_assoc_type_path(db, resolver, path) {
+            return ty;
+        }
+        if let Some(ty) = Ty::from_param_assoc_type_path(db, resolver, path) {
+            return ty;
+        }
+
         // Resolve the path (in type namespace)
         let resolution = resolver.resolve_path(db, path).take_types();
 
@@ -113,6 +124,96 @@ impl Ty {
         ty.subst(&substs)
     }
 
+    /// Lowers a trait object type like `dyn Iterator<Item = u32>`.
+    // FIXME: only the first bound that is a trait is kept, and lifetime
+    // bounds are ignored
+    fn from_hir_dyn_trait(db: &impl HirDatabase, resolver: &Resolver, paths: &[Path]) -> Self {
+        let (path, trait_) = match dyn_trait_path(db, resolver, paths) {
+            Some(it) => it,
+            None => return Ty::Unknown,
+        };
+        // `Self` of the trait is the trait object itself, so it's left out
+        let substs = TraitRef::substs_from_path(db, resolver, path, trait_, None);
+        let mut parameters: Vec<Ty> = substs.iter().skip(1).cloned().collect();
+        let segment = path.segments.last().expect("path should have at least one segment");
+        let bindings = segment.args_and_bindings.as_ref().map_or(&[][..], |it| &it.bindings[..]);
+        for assoc_type in trait_.trait_data(db).associated_types() {
+            let name = assoc_type.name(db);
+            let ty = match bindings.iter().find(|(n, _)| Some(n) == name.as_ref()) {
+                Some((_, type_ref)) => Ty::from_hir(db, resolver, type_ref),
+                None => Ty::Unknown,
+            };
+            parameters.push(ty);
+        }
+        Ty::apply(TypeCtor::Dyn(trait_), parameters.into())
+    }
+
+    /// Lowers a `Self::Assoc` path inside an impl (e.g. in its where clauses)
+    /// to the associated type `Assoc` defined in that impl. Inside a trait,
+    /// this becomes the associated type `<Self as Trait>::Assoc`, which is
+    /// only resolved during inference.
+    // FIXME: look for the associated type in the implemented trait and in the
+    // other impls of the self type as well
+    fn from_self_assoc_type_path(
+        db: &impl HirDatabase,
+        resolver: &Resolver,
+        path: &Path,
+    ) -> Option<Ty> {
+        if path.kind != PathKind::Plain || path.segments.len() != 2 {
+            return None;
+        }
+        let self_segment = &path.segments[0];
+        if self_segment.name != Name::self_type() || self_segment.args_and_bindings.is_some() {
+            return None;
+        }
+        let name = &path.segments[1].name;
+        let impl_block = match resolver.resolve_name(db, &Name::self_type()).take_types()? {
+            Resolution::SelfType(impl_block) => impl_block,
+            Resolution::GenericParam(_) => {
+                let trait_ = resolver.enclosing_trait()?;
+                let type_alias = trait_
+                    .trait_data(db)
+                    .associated_types()
+                    .find(|it| it.name(db).as_ref() == Some(name))?;
+                let substs = Substs::identity(&trait_.generic_params(db));
+                return Some(Ty::apply(TypeCtor::AssocType(type_alias), substs));
+            }
+            _ => return None,
+        };
+        let type_alias = impl_block.items(db).into_iter().find_map(|item| match item {
+            ImplItem::TypeAlias(t) if t.name(db).as_ref() == Some(name) => Some(t),
+            _ => None,
+        })?;
+        Some(db.type_for_def(type_alias.into(), Namespace::Types))
+    }
+
+    /// Lowers a path `T::Assoc` for a type param `T` to the associated type
+    /// `Assoc` of the trait in the bounds of `T` that declares it, e.g. to
+    /// `<T as Iterator>::Item` for `T::Item` with `T: Iterator + Clone`. If
+    /// several of the traits declare it, the path is ambiguous and becomes
+    /// `{unknown}`.
+    fn from_param_assoc_type_path(
+        db: &impl HirDatabase,
+        resolver: &Resolver,
+        path: &Path,
+    ) -> Option<Ty> {
+        let (param, name) = param_assoc_type_path(db, resolver, path)?;
+        match &param_assoc_type_candidates(db, resolver, &param, name)[..] {
+            [] => None,
+            [(trait_, type_alias)] => {
+                // FIXME: the args of the bound aren't lowered, as they may
+                // refer to the associated type itself, like in `T: Foo<T::Item>`
+                let count = trait_.generic_params(db).count_params_including_parent();
+                let substs = iter::once(param)
+                    .chain(iter::repeat(Ty::Unknown))
+                    .take(count)
+                    .collect::<Vec<_>>();
+                Some(Ty::apply(TypeCtor::AssocType(*type_alias), Substs(substs.into())))
+            }
+            _ => Some(Ty::Unknown),
+        }
+    }
+
     pub(super) fn substs_from_path_segment(
         db: &impl HirDatabase,
         resolver: &Resolver,
@@ -207,6 +308,100 @@ pub(super) fn substs_from_path_segment(
     Substs(substs.into())
 }
 
+/// The path of the trait of a trait object `dyn paths`, which is the first of
+/// the bounds that is a trait, like `Iterator` in `dyn Iterator + Send`.
+fn dyn_trait_path<'a>(
+    db: &impl HirDatabase,
+    resolver: &Resolver,
+    paths: &'a [Path],
+) -> Option<(&'a Path, Trait)> {
+    paths.iter().find_map(|path| match resolver.resolve_path(db, path).take_types()? {
+        Resolution::Def(ModuleDef::Trait(t)) => Some((path, t)),
+        _ => None,
+    })
+}
+
+/// The associated types of the trait of the trait object `dyn paths` that
+/// have neither a binding nor a default, like `Item` for `dyn Iterator`.
+pub(super) fn missing_assoc_type_bindings(
+    db: &impl HirDatabase,
+    resolver: &Resolver,
+    paths: &[Path],
+) -> Vec<Name> {
+    let (path, trait_) = match dyn_trait_path(db, resolver, paths) {
+        Some(it) => it,
+        None => return Vec::new(),
+    };
+    let segment = path.segments.last().expect("path should have at least one segment");
+    let bindings = segment.args_and_bindings.as_ref().map_or(&[][..], |it| &it.bindings[..]);
+    trait_
+        .trait_data(db)
+        .associated_types()
+        .filter(|it| *it.type_ref(db) == TypeRef::Error)
+        .filter_map(|it| it.name(db))
+        .filter(|name| !bindings.iter().any(|(n, _)| n == name))
+        .collect()
+}
+
+/// Splits a path `T::Assoc` for a type param `T` into `T` and the name
+/// `Assoc`.
+pub(super) fn param_assoc_type_path<'a>(
+    db: &impl HirDatabase,
+    resolver: &Resolver,
+    path: &'a Path,
+) -> Option<(Ty, &'a Name)> {
+    if path.kind != PathKind::Plain || path.segments.len() != 2 {
+        return None;
+    }
+    let param_segment = &path.segments[0];
+    if param_segment.args_and_bindings.is_some() {
+        return None;
+    }
+    match resolver.resolve_name(db, &param_segment.name).take_types()? {
+        Resolution::GenericParam(idx) => {
+            let param = Ty::Param { idx, name: param_segment.name.clone() };
+            Some((param, &path.segments[1].name))
+        }
+        _ => None,
+    }
+}
+
+/// The associated types named `name` of the traits in the bounds of the type
+/// param `param`. There is more than one if the name is ambiguous, like
+/// `Item` for `T: Iterator + IntoIterator`.
+// FIXME: look into the supertraits of the bounds as well
+pub(super) fn param_assoc_type_candidates(
+    db: &impl HirDatabase,
+    resolver: &Resolver,
+    param: &Ty,
+    name: &Name,
+) -> Vec<(Trait, TypeAlias)> {
+    let param_type_ref = match param {
+        Ty::Param { name: param_name, .. } => TypeRef::Path(param_name.clone().into()),
+        _ => return Vec::new(),
+    };
+    let mut candidates: Vec<(Trait, TypeAlias)> = Vec::new();
+    for pred in resolver.where_predicates_in_scope() {
+        if pred.type_ref != param_type_ref {
+            continue;
+        }
+        let trait_ = match resolver.resolve_path(db, &pred.trait_ref).take_types() {
+            Some(Resolution::Def(ModuleDef::Trait(t))) => t,
+            _ => continue,
+        };
+        // the same trait may be in several bounds, like `T: Iterator + Iterator`
+        if candidates.iter().any(|(t, _)| *t == trait_) {
+            continue;
+        }
+        let type_alias =
+            trait_.trait_data(db).associated_types().find(|it| it.name(db).as_ref() == Some(name));
+        if let Some(type_alias) = type_alias {
+            candidates.push((trait_, type_alias));
+        }
+    }
+    candidates
+}
+
 impl TraitRef {
     pub(crate) fn from_hir(
         db: &impl HirDatabase,
@@ -222,24 +417,37 @@ impl TraitRef {
             Resolution::Def(ModuleDef::Trait(tr)) => tr,
             _ => return None,
         };
-        let mut substs = Self::substs_from_path(db, resolver, path, resolved);
-        if let Some(self_ty) = explicit_self_ty {
-            // FIXME this could be nicer
-            let mut substs_vec = substs.0.to_vec();
-            substs_vec[0] = self_ty;
-            substs.0 = substs_vec.into();
-        }
+        let substs = Self::substs_from_path(db, resolver, path, resolved, explicit_self_ty);
         Some(TraitRef { trait_: resolved, substs })
     }
 
+    /// The args of a trait in `path`. The ones that are left out get the
+    /// defaults of their params, like `i32` in `impl Foo for Bar` for
+    /// `trait Foo<T = i32>`.
     fn substs_from_path(
         db: &impl HirDatabase,
         resolver: &Resolver,
         path: &Path,
         resolved: Trait,
+        explicit_self_ty: Option<Ty>,
     ) -> Substs {
         let segment = path.segments.last().expect("path should have at least one segment");
-        substs_from_path_segment(db, resolver, segment, &resolved.generic_params(db), true)
+        let generics = resolved.generic_params(db);
+        let mut substs =
+            substs_from_path_segment(db, resolver, segment, &generics, true).0.to_vec();
+        // `Self` comes first, so that defaults like `Rhs = Self` refer to it
+        if let Some(self_ty) = explicit_self_ty {
+            substs[0] = self_ty;
+        }
+        let supplied = 1 + segment.args_and_bindings.as_ref().map_or(0, |it| it.args.len());
+        let def_resolver = GenericDef::from(resolved).resolver(db);
+        for param in generics.params.iter().filter(|p| p.idx as usize >= supplied) {
+            if let Some(default) = &param.default {
+                let ty = Ty::from_hir(db, &def_resolver, default);
+                substs[param.idx as usize] = ty.subst(&Substs(substs.clone().into()));
+            }
+        }
+        Substs(substs.into())
     }
 
     pub(crate) fn for_trait(db: &impl HirDatabase, trait_: Trait) -> TraitRef {
@@ -248,6 +456,23 @@ impl TraitRef {
     }
 }
 
+/// The traits in the bounds of the opaque type of `type_alias`, like
+/// `Iterator` for `type Foo = impl Iterator<Item = u32>;`.
+pub(crate) fn opaque_type_bounds(db: &impl HirDatabase, type_alias: TypeAlias) -> Vec<Trait> {
+    let paths = match &*type_alias.type_ref(db) {
+        TypeRef::ImplTrait(paths) => paths.clone(),
+        _ => return Vec::new(),
+    };
+    let resolver = type_alias.resolver(db);
+    paths
+        .iter()
+        .filter_map(|path| match resolver.resolve_path(db, path).take_types()? {
+            Resolution::Def(ModuleDef::Trait(it)) => Some(it),
+            _ => None,
+        })
+        .collect()
+}
+
 /// Build the declared type of an item. This depends on the namespace; e.g. for
 /// `struct Foo(usize)`, we have two types: The type of the struct itself, and
 /// the constructor function `(usize) -> Foo` which lives in the values
@@ -396,6 +621,11 @@ fn type_for_type_alias(db: &impl HirDatabase, t: TypeAlias) -> Ty {
     let resolver = t.resolver(db);
     let type_ref = t.type_ref(db);
     let substs = Substs::identity(&generics);
+    // `type Foo = impl Trait;` is an opaque type that is only known to
+    // implement its bounds
+    if let TypeRef::ImplTrait(_) = &*type_ref {
+        return Ty::apply(TypeCtor::Opaque(t), substs);
+    }
     let inner = Ty::from_hir(db, &resolver, &type_ref);
     inner.subst(&substs)
 }
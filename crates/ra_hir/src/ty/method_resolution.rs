@@ -2,21 +2,22 @@
 //! For details about how this works in rustc, see the method lookup page in the
 //! [rustc guide](https://rust-lang.github.io/rustc-guide/method-lookup.html)
 //! and the corresponding code mostly in librustc_typeck/check/method/probe.rs.
-use std::sync::Arc;
+use std::{iter, sync::Arc};
 
-use rustc_hash::FxHashMap;
+use rustc_hash::{FxHashMap, FxHashSet};
 
 use crate::{
-    HirDatabase, Module, Crate, Name, Function, Trait,
+    HirDatabase, Module, Crate, Name, Function, Trait, ModuleDef,
     impl_block::{ImplId, ImplBlock, ImplItem},
-    ty::{Ty, TypeCtor},
+    ty::{Ty, TypeCtor, ApplicationTy},
     nameres::CrateModuleId,
-    resolve::Resolver,
+    resolve::{Resolver, Resolution},
+    type_ref::TypeRef,
     traits::TraitItem,
     generics::HasGenericParams,
     ty::primitive::{UncertainIntTy, UncertainFloatTy}
 };
-use super::{TraitRef, Canonical};
+use super::{TraitRef, Canonical, lower::opaque_type_bounds};
 
 /// This is used as a key for indexing impls.
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
@@ -42,6 +43,10 @@ pub struct CrateImplBlocks {
     krate: Crate,
     impls: FxHashMap<TyFingerprint, Vec<(CrateModuleId, ImplId)>>,
     impls_by_trait: FxHashMap<Trait, Vec<(CrateModuleId, ImplId)>>,
+    /// The trait impls which aren't blanket impls, by trait and self type.
+    trait_impls_by_ty: FxHashMap<(Trait, TyFingerprint), Vec<(CrateModuleId, ImplId)>>,
+    /// The traits with a blanket impl like `impl<T> Trait for T`.
+    traits_with_blanket_impls: FxHashSet<Trait>,
 }
 
 impl CrateImplBlocks {
@@ -67,6 +72,19 @@ impl CrateImplBlocks {
         )
     }
 
+    /// Whether there is an impl for `tr` and the type of `ty` in this crate,
+    /// not counting blanket impls.
+    pub fn has_impl_for_trait_and_ty(&self, tr: &Trait, ty: &Ty) -> bool {
+        TyFingerprint::for_impl(ty)
+            .map_or(false, |fp| self.trait_impls_by_ty.contains_key(&(*tr, fp)))
+    }
+
+    /// Whether there is a blanket impl like `impl<T> Trait for T` for `tr` in
+    /// this crate.
+    pub fn has_blanket_impl_for_trait(&self, tr: &Trait) -> bool {
+        self.traits_with_blanket_impls.contains(tr)
+    }
+
     fn collect_recursive(&mut self, db: &impl HirDatabase, module: &Module) {
         let module_impl_blocks = db.impls_in_module(module.clone());
 
@@ -80,6 +98,14 @@ impl CrateImplBlocks {
                     .entry(tr.trait_)
                     .or_insert_with(Vec::new)
                     .push((module.module_id, impl_id));
+                if impl_block.is_blanket(db) {
+                    self.traits_with_blanket_impls.insert(tr.trait_);
+                } else if let Some(target_ty_fp) = TyFingerprint::for_impl(&target_ty) {
+                    self.trait_impls_by_ty
+                        .entry((tr.trait_, target_ty_fp))
+                        .or_insert_with(Vec::new)
+                        .push((module.module_id, impl_id));
+                }
             } else {
                 if let Some(target_ty_fp) = TyFingerprint::for_impl(&target_ty) {
                     self.impls
@@ -103,6 +129,8 @@ impl CrateImplBlocks {
             krate,
             impls: FxHashMap::default(),
             impls_by_trait: FxHashMap::default(),
+            trait_impls_by_ty: FxHashMap::default(),
+            traits_with_blanket_impls: FxHashSet::default(),
         };
         if let Some(module) = krate.root_module(db) {
             crate_impl_blocks.collect_recursive(db, &module);
@@ -124,6 +152,7 @@ fn def_crate(db: &impl HirDatabase, cur_crate: Crate, ty: &Ty) -> Option<Crate>
                 db.lang_item(cur_crate, i.ty_to_string().into())?.krate(db)
             }
             TypeCtor::Str => db.lang_item(cur_crate, "str".into())?.krate(db),
+            TypeCtor::Dyn(trait_) => trait_.module(db).krate(db),
             _ => None,
         },
         _ => None,
@@ -159,15 +188,30 @@ pub(crate) fn iterate_method_candidates<T>(
     // find in the end takes &self, we still do the autoderef step (just as
     // rustc does an autoderef and then autoref again).
 
+    // The methods of blanket impls like `impl<T> Trait for T` apply at every
+    // autoderef step, so they are only considered after all the others;
+    // otherwise they'd win over inherent methods of the derefed types.
+    // FIXME: rustc still prefers them over other trait methods of later steps
     let krate = resolver.krate()?;
-    for derefed_ty in ty.value.clone().autoderef(db) {
-        let derefed_ty = Canonical { value: derefed_ty, num_vars: ty.num_vars };
-        if let Some(result) = iterate_inherent_methods(&derefed_ty, db, name, krate, &mut callback)
+    let derefed_tys: Vec<Canonical<Ty>> = ty
+        .value
+        .clone()
+        .autoderef(db, resolver)
+        .map(|derefed_ty| Canonical { value: derefed_ty, num_vars: ty.num_vars })
+        .collect();
+    for derefed_ty in derefed_tys.iter() {
+        if let Some(result) = iterate_inherent_methods(derefed_ty, db, name, krate, &mut callback) {
+            return Some(result);
+        }
+        if let Some(result) =
+            iterate_trait_method_candidates(derefed_ty, db, resolver, name, false, &mut callback)
         {
             return Some(result);
         }
+    }
+    for derefed_ty in derefed_tys.iter() {
         if let Some(result) =
-            iterate_trait_method_candidates(&derefed_ty, db, resolver, name, &mut callback)
+            iterate_trait_method_candidates(derefed_ty, db, resolver, name, true, &mut callback)
         {
             return Some(result);
         }
@@ -175,20 +219,52 @@ pub(crate) fn iterate_method_candidates<T>(
     None
 }
 
+/// Whether the methods of `trait_` for `ty` can only come from a blanket impl
+/// like `impl<T> Trait for T`, i.e. there is one in the trait's crate or in
+/// `krate`, but no other impl for the type of `ty`.
+fn is_blanket_impl_only(db: &impl HirDatabase, krate: Crate, trait_: Trait, ty: &Ty) -> bool {
+    let trait_krate = trait_.module(db).krate(db);
+    let mut has_blanket_impl = false;
+    for krate in iter::once(krate).chain(trait_krate) {
+        let impls = db.impls_in_crate(krate);
+        if impls.has_impl_for_trait_and_ty(&trait_, ty) {
+            return false;
+        }
+        has_blanket_impl |= impls.has_blanket_impl_for_trait(&trait_);
+    }
+    has_blanket_impl
+}
+
+/// Iterates over the methods of the traits only implemented for `ty` by a
+/// blanket impl if `blanket`, or of the other traits if not.
 fn iterate_trait_method_candidates<T>(
     ty: &Canonical<Ty>,
     db: &impl HirDatabase,
     resolver: &Resolver,
     name: Option<&Name>,
+    blanket: bool,
     mut callback: impl FnMut(&Ty, Function) -> Option<T>,
 ) -> Option<T> {
     let krate = resolver.krate()?;
-    'traits: for t in resolver.traits_in_scope() {
+    // the traits a type param is bounded by are implemented without further
+    // checks, and don't need to be in scope
+    let bound_traits = param_bound_traits(db, resolver, &ty.value);
+    let mut traits: Vec<Trait> = resolver.traits_in_scope().collect();
+    for t in bound_traits.iter() {
+        if !traits.contains(t) {
+            traits.push(*t);
+        }
+    }
+    'traits: for t in traits {
+        let known_implemented = bound_traits.contains(&t);
+        if (!known_implemented && is_blanket_impl_only(db, krate, t, &ty.value)) != blanket {
+            continue;
+        }
         let data = t.trait_data(db);
         // we'll be lazy about checking whether the type implements the
         // trait, but if we find out it doesn't, we'll skip the rest of the
         // iteration
-        let mut known_implemented = false;
+        let mut known_implemented = known_implemented;
         for item in data.items() {
             match item {
                 &TraitItem::Function(m) => {
@@ -214,6 +290,30 @@ fn iterate_trait_method_candidates<T>(
     None
 }
 
+/// The traits in the bounds of `ty` if it is a type param, like `Trait` in
+/// `T: Trait`, the trait of a trait object, or the bounds of an opaque type.
+fn param_bound_traits(db: &impl HirDatabase, resolver: &Resolver, ty: &Ty) -> Vec<Trait> {
+    let param_type_ref = match ty {
+        Ty::Param { name, .. } => TypeRef::Path(name.clone().into()),
+        Ty::Apply(ApplicationTy { ctor: TypeCtor::Dyn(trait_), .. }) => return vec![*trait_],
+        Ty::Apply(ApplicationTy { ctor: TypeCtor::Opaque(type_alias), .. }) => {
+            return opaque_type_bounds(db, *type_alias)
+        }
+        _ => return Vec::new(),
+    };
+    resolver
+        .where_predicates_in_scope()
+        // subjects like `Self::X` in impls may stand for the param as well
+        .filter(|pred| {
+            pred.type_ref == param_type_ref || Ty::from_hir(db, resolver, &pred.type_ref) == *ty
+        })
+        .filter_map(|pred| match resolver.resolve_path(db, &pred.trait_ref).take_types()? {
+            Resolution::Def(ModuleDef::Trait(t)) => Some(t),
+            _ => None,
+        })
+        .collect()
+}
+
 fn iterate_inherent_methods<T>(
     ty: &Canonical<Ty>,
     db: &impl HirDatabase,
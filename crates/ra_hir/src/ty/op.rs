@@ -43,6 +43,25 @@ pub(super) fn binary_op_return_ty(op: BinaryOp, rhs_ty: Ty) -> Ty {
     }
 }
 
+/// The lang item name of the trait implementing an overloadable operator,
+/// like `add` for `Add`.
+pub(super) fn binary_op_lang_item(op: BinaryOp) -> Option<&'static str> {
+    let name = match op {
+        BinaryOp::Addition => "add",
+        BinaryOp::Subtraction => "sub",
+        BinaryOp::Multiplication => "mul",
+        BinaryOp::Division => "div",
+        BinaryOp::Remainder => "rem",
+        BinaryOp::LeftShift => "shl",
+        BinaryOp::RightShift => "shr",
+        BinaryOp::BitwiseAnd => "bitand",
+        BinaryOp::BitwiseOr => "bitor",
+        BinaryOp::BitwiseXor => "bitxor",
+        _ => return None,
+    };
+    Some(name)
+}
+
 pub(super) fn binary_op_rhs_expectation(op: BinaryOp, lhs_ty: Ty) -> Ty {
     match op {
         BinaryOp::BooleanAnd | BinaryOp::BooleanOr => Ty::simple(TypeCtor::Bool),
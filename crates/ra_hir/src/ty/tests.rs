@@ -12,7 +12,7 @@ use crate::{
     ty::display::HirDisplay,
     ty::InferenceResult,
     expr::BodySourceMap,
-    SourceAnalyzer,
+    source_binder, SourceAnalyzer, PathResolution, DefWithBody, InferenceMode, Ty,
 };
 
 // These tests compare the inference results for all expressions in a file
@@ -1036,6 +1036,28 @@ fn test() {
     );
 }
 
+#[test]
+fn infer_enum_variant_uses_enum_generics() {
+    let t = type_at(
+        r#"
+//- /main.rs
+enum Option<T> { Some(T), None }
+use Option::*;
+fn test() { Some(5)<|>; }
+"#,
+    );
+    assert_eq!(t, "Option<i32>");
+    let t = type_at(
+        r#"
+//- /main.rs
+enum Option<T> { Some(T), None }
+use Option::*;
+fn test() { None::<&str><|>; }
+"#,
+    );
+    assert_eq!(t, "Option<&str>");
+}
+
 #[test]
 fn infer_generics_in_patterns() {
     assert_snapshot_matches!(
@@ -1507,6 +1529,21 @@ fn test() {
     );
 }
 
+#[test]
+fn infer_associated_fn_with_turbofish_on_type() {
+    let t = type_at(
+        r#"
+//- /main.rs
+struct Vec<T> {}
+impl<T> Vec<T> {
+    fn new() -> Vec<T> { loop {} }
+}
+fn test() { Vec::<u8>::new()<|>; }
+"#,
+    );
+    assert_eq!(t, "Vec<u8>");
+}
+
 #[test]
 fn infer_type_alias() {
     assert_snapshot_matches!(
@@ -2145,6 +2182,136 @@ fn test() {
     );
 }
 
+#[test]
+fn infer_self_assoc_fn_in_generic_impl() {
+    let t = type_at(
+        r#"
+//- /main.rs
+struct Foo<T>(T);
+impl<T> Foo<T> {
+    fn helper(t: T) -> Foo<T> { Foo(t) }
+    fn new(t: T) -> Self {
+        let x = Self::helper(t);
+        x<|>;
+        x
+    }
+}
+"#,
+    );
+    assert_eq!(t, "Foo<T>");
+}
+
+#[test]
+fn infer_phantom_data_only_constructor_with_turbofish() {
+    let t = type_at(
+        r#"
+//- /main.rs
+struct PhantomData<T>;
+struct Marker<T>(PhantomData<T>);
+fn test() {
+    let m = Marker::<u8>(PhantomData);
+    m<|>;
+}
+"#,
+    );
+    assert_eq!(t, "Marker<u8>");
+}
+
+#[test]
+fn inherent_method_is_preferred_over_blanket_impl_method() {
+    let t = type_at(
+        r#"
+//- /main.rs
+trait Tr {
+    fn foo(&self) -> i8;
+}
+impl<T> Tr for T {}
+struct S;
+impl S {
+    fn foo(&self) -> u32 {}
+}
+fn test(s: &S) {
+    let x = s.foo();
+    x<|>;
+}
+"#,
+    );
+    assert_eq!(t, "u32");
+}
+
+#[test]
+fn trait_method_of_non_blanket_impl_is_preferred_over_inherent_method_of_deref() {
+    let t = type_at(
+        r#"
+//- /main.rs
+#[lang = "deref"]
+trait Deref {
+    type Target;
+    fn deref(&self) -> &Self::Target;
+}
+trait Marker {}
+trait Tr {
+    fn foo(&self) -> i8;
+}
+impl<T: Marker> Tr for T {}
+struct S;
+impl S {
+    fn foo(&self) -> u32 {}
+}
+struct D;
+impl Deref for D {
+    type Target = S;
+}
+impl Tr for D {
+    fn foo(&self) -> i8 {}
+}
+fn test(d: D) {
+    let x = d.foo();
+    x<|>;
+}
+"#,
+    );
+    assert_eq!(t, "i8");
+}
+
+#[test]
+fn infer_trait_method_with_default_trait_arg() {
+    let t = type_at(
+        r#"
+//- /main.rs
+trait Foo<T = i32> {
+    fn foo(&self) -> T;
+}
+struct Bar;
+impl Foo for Bar {}
+fn test() {
+    let x = Bar.foo();
+    x<|>;
+}
+"#,
+    );
+    assert_eq!(t, "i32");
+}
+
+#[test]
+fn infer_trait_method_with_default_trait_arg_referring_to_self() {
+    let t = type_at(
+        r#"
+//- /main.rs
+trait Add<Rhs = Self> {
+    fn add(&self) -> Rhs;
+}
+struct Bar;
+impl Add for Bar {}
+fn test() {
+    let x = Bar.add();
+    x<|>;
+}
+"#,
+    );
+    assert_eq!(t, "Bar");
+}
+
 #[test]
 fn infer_trait_assoc_method() {
     assert_snapshot_matches!(
@@ -2267,6 +2434,53 @@ fn test<U, T: Trait<U>>(t: T) {
     );
 }
 
+#[test]
+fn method_resolution_with_inline_bound_on_impl() {
+    let t = type_at(
+        r#"
+//- /main.rs
+trait Clone {}
+trait Trait {
+    fn method(&self) -> u32;
+}
+struct A;
+impl Clone for A {}
+struct S<T>(T);
+impl<T: Clone> Trait for S<T> {
+    fn method(&self) -> u32 { 0 }
+}
+fn test(s: S<A>) {
+    let x = s.method();
+    x<|>;
+}
+"#,
+    );
+    assert_eq!(t, "u32");
+}
+
+#[test]
+fn method_resolution_with_unsatisfied_inline_bound_on_impl() {
+    let t = type_at(
+        r#"
+//- /main.rs
+trait Clone {}
+trait Trait {
+    fn method(&self) -> u32;
+}
+struct NotClone;
+struct S<T>(T);
+impl<T: Clone> Trait for S<T> {
+    fn method(&self) -> u32 { 0 }
+}
+fn test(s: S<NotClone>) {
+    let x = s.method();
+    x<|>;
+}
+"#,
+    );
+    assert_eq!(t, "{unknown}");
+}
+
 #[test]
 fn infer_with_multiple_trait_impls() {
     assert_snapshot_matches!(
@@ -2299,6 +2513,35 @@ fn test() {
     );
 }
 
+#[test]
+fn infer_into_from_expected_type() {
+    assert_snapshot_matches!(
+        infer(r#"
+trait From<T> {
+    fn from(t: T) -> Self;
+}
+trait Into<T> {
+    fn into(self) -> T;
+}
+impl<T, U: From<T>> Into<U> for T;
+struct S;
+struct String;
+impl From<S> for String;
+fn test(s: S) {
+    let x: String = s.into();
+}
+"#),
+        @r###"
+[29; 30) 't': T
+[74; 78) 'self': Self
+[181; 182) 's': S
+[187; 220) '{     ...o(); }': ()
+[197; 198) 'x': String
+[209; 210) 's': S
+[209; 217) 's.into()': String"###
+    );
+}
+
 #[test]
 fn infer_project_associated_type() {
     assert_snapshot_matches!(
@@ -2335,9 +2578,144 @@ fn test<T: Iterable<Item=u32>>() {
 "#),
         @r###"
 [67; 100) '{     ...own; }': ()
-[77; 78) 'y': {unknown}
-[90; 97) 'unknown': {unknown}"###
+[77; 78) 'y': u32
+[90; 97) 'unknown': u32"###
+    );
+}
+
+#[test]
+fn infer_param_assoc_type_from_the_bound_declaring_it() {
+    let t = type_at(
+        r#"
+//- /main.rs
+trait Iterator {
+    type Item;
+}
+trait Clone {}
+fn test<T: Iterator<Item = u32> + Clone>(t: T) {
+    let x: T::Item = unknown;
+    x<|>;
+}
+"#,
+    );
+    assert_eq!(t, "u32");
+}
+
+#[test]
+fn infer_call_of_field_with_fn_bound() {
+    let t = type_at(
+        r#"
+//- /main.rs
+trait Fn<Args> {}
+struct S<F: Fn() -> i32> {
+    f: F,
+}
+impl<F: Fn() -> i32> S<F> {
+    fn call(&self) {
+        let x = (self.f)();
+        x<|>;
+    }
+}
+"#,
+    );
+    assert_eq!(t, "i32");
+}
+
+#[test]
+fn infer_closure_param_from_fn_bound_of_callee() {
+    let t = type_at(
+        r#"
+//- /main.rs
+trait Fn<Args> {}
+fn apply<F: Fn(u8) -> u8>(f: F) -> u8 { f(1) }
+fn test() {
+    apply(|x| { x<|>; x });
+}
+"#,
+    );
+    assert_eq!(t, "u8");
+}
+
+#[test]
+fn infer_method_call_on_opaque_type_alias() {
+    let t = type_at(
+        r#"
+//- /main.rs
+trait Shape {
+    fn area(&self) -> u32;
+}
+type Foo = impl Shape;
+fn test(foo: Foo) {
+    let a = foo.area();
+    a<|>;
+}
+"#,
     );
+    assert_eq!(t, "u32");
+}
+
+#[test]
+fn ambiguous_param_assoc_type_is_diagnosed() {
+    let diagnostics = MockDatabase::with_files(
+        r#"
+//- /lib.rs
+trait Iterator {
+    type Item;
+}
+trait IntoIterator {
+    type Item;
+}
+fn test<T: Iterator + IntoIterator>(t: T) {
+    let x: T::Item = unknown;
+}
+"#,
+    )
+    .diagnostics();
+
+    assert_snapshot_matches!(diagnostics, @r###"
+"x": ambiguous associated type `Item`
+"###
+    );
+}
+
+#[test]
+fn trait_object_without_assoc_type_binding_is_diagnosed() {
+    let diagnostics = MockDatabase::with_files(
+        r#"
+//- /lib.rs
+trait Iterator {
+    type Item;
+}
+fn test(it: &dyn Iterator) {
+    let x: &dyn Iterator<Item = u8> = it;
+}
+"#,
+    )
+    .diagnostics();
+
+    assert_snapshot_matches!(diagnostics, @r###"
+"it": the value of the associated type `Item` must be specified
+"###
+    );
+}
+
+#[test]
+fn trait_object_with_defaulted_assoc_type_is_not_diagnosed() {
+    let diagnostics = MockDatabase::with_files(
+        r#"
+//- /lib.rs
+trait Iterator {
+    type Item;
+}
+trait Foo {
+    type Bar = u32;
+}
+fn test(it: &dyn Iterator<Item = u8>, foo: &dyn Foo) {}
+"#,
+    )
+    .diagnostics();
+
+    assert_eq!("\n", diagnostics);
 }
 
 #[test]
@@ -2518,6 +2896,531 @@ fn test() { (&S).foo()<|>; }
     assert_eq!(t, "i8");
 }
 
+#[test]
+fn infer_self_assoc_type_in_impl_with_where_clause() {
+    let t = type_at(
+        r#"
+//- /main.rs
+trait Clone {}
+trait Foo { type X; }
+struct Bar<T>(T);
+impl<T> Foo for Bar<T> where T: Clone {
+    type X = u64;
+    fn get(&self, x: Self::X) { x<|>; }
+}
+"#,
+    );
+    assert_eq!(t, "u64");
+}
+
+#[test]
+fn infer_self_assoc_type_bound_in_impl_where_clause() {
+    let t = type_at(
+        r#"
+//- /main.rs
+trait Tr { fn tr(&self) -> u8; }
+trait Foo { type X; }
+struct Bar<T>(T);
+impl<T> Foo for Bar<T> where Self::X: Tr {
+    type X = T;
+    fn get(&self, x: Self::X) { x.tr()<|>; }
+}
+"#,
+    );
+    assert_eq!(t, "u8");
+}
+
+#[test]
+fn method_resolution_through_generic_deref() {
+    let t = type_at(
+        r#"
+//- /main.rs
+#[lang = "deref"]
+trait Deref {
+    type Target;
+    fn deref(&self) -> &Self::Target;
+}
+struct String;
+impl String { fn len(&self) -> usize { 0 } }
+struct P<T>(T);
+impl<T> Deref for P<T> {
+    type Target = T;
+    fn deref(&self) -> &T { &self.0 }
+}
+fn test(p: P<String>) { p.len()<|>; }
+"#,
+    );
+    assert_eq!(t, "usize");
+}
+
+#[test]
+fn infer_for_loop_over_vec() {
+    let (mut db, pos) = MockDatabase::with_position(
+        r#"
+//- /main.rs
+use std::{vec::Vec, string::String};
+
+fn test(items: Vec<String>) {
+    for x in items {
+        x<|>;
+    }
+}
+
+//- /std.rs
+pub mod iter {
+    pub trait IntoIterator {
+        type Item;
+    }
+}
+pub mod string {
+    pub struct String;
+}
+pub mod vec {
+    pub struct Vec<T>(T);
+    impl<T> crate::iter::IntoIterator for Vec<T> {
+        type Item = T;
+    }
+}
+"#,
+    );
+    db.set_crate_graph_from_fixture(crate_graph! {
+        "main": ("/main.rs", ["std"]),
+        "std": ("/std.rs", []),
+    });
+    assert_eq!("String", type_at_pos(&db, pos));
+}
+
+#[test]
+fn infer_try_with_single_from_conversion() {
+    let (mut db, pos) = MockDatabase::with_position(
+        r#"
+//- /main.rs
+use std::{result::Result, convert::From};
+
+struct IoError;
+struct MyError;
+impl From<IoError> for MyError {}
+
+fn read() -> Result<u32, IoError> {}
+
+fn test() -> Result<(), MyError> {
+    let x = read()?;
+    x<|>;
+}
+
+//- /std.rs
+pub mod ops {
+    pub trait Try {
+        type Ok;
+        type Error;
+    }
+}
+pub mod convert {
+    pub trait From<T> {}
+}
+pub mod result {
+    pub enum Result<T, E> { Ok(T), Err(E) }
+    impl<T, E> crate::ops::Try for Result<T, E> {
+        type Ok = T;
+        type Error = E;
+    }
+}
+"#,
+    );
+    db.set_crate_graph_from_fixture(crate_graph! {
+        "main": ("/main.rs", ["std"]),
+        "std": ("/std.rs", []),
+    });
+    assert_eq!("u32", type_at_pos(&db, pos));
+    assert_eq!("\n", db.diagnostics());
+}
+
+#[test]
+fn try_without_single_from_conversion_is_diagnosed() {
+    let mut db = MockDatabase::with_files(
+        r#"
+//- /main.rs
+use std::{result::Result, convert::From};
+
+struct IoError;
+struct ParseError;
+struct MyError;
+impl From<IoError> for ParseError {}
+impl From<ParseError> for MyError {}
+
+fn read() -> Result<u32, IoError> {}
+
+fn test() -> Result<(), MyError> {
+    let x = read()?;
+}
+
+//- /std.rs
+pub mod ops {
+    pub trait Try {
+        type Ok;
+        type Error;
+    }
+}
+pub mod convert {
+    pub trait From<T> {}
+}
+pub mod result {
+    pub enum Result<T, E> { Ok(T), Err(E) }
+    impl<T, E> crate::ops::Try for Result<T, E> {
+        type Ok = T;
+        type Error = E;
+    }
+}
+"#,
+    );
+    db.set_crate_graph_from_fixture(crate_graph! {
+        "main": ("/main.rs", ["std"]),
+        "std": ("/std.rs", []),
+    });
+    assert_snapshot_matches!(db.diagnostics(), @r###"
+"read()?": `?` can't convert the error type `IoError` into `MyError`
+"###
+    );
+}
+
+#[test]
+fn try_conversion_diagnostic_prints_param_names() {
+    let mut db = MockDatabase::with_files(
+        r#"
+//- /main.rs
+use std::{result::Result, vec::Vec};
+
+struct MyError;
+
+fn read<T>() -> Result<u32, Vec<T>> {}
+
+fn test<T>() -> Result<(), MyError> {
+    read::<T>()?;
+}
+
+//- /std.rs
+pub mod ops {
+    pub trait Try {
+        type Ok;
+        type Error;
+    }
+}
+pub mod convert {
+    pub trait From<T> {}
+}
+pub mod result {
+    pub enum Result<T, E> { Ok(T), Err(E) }
+    impl<T, E> crate::ops::Try for Result<T, E> {
+        type Ok = T;
+        type Error = E;
+    }
+}
+pub mod vec {
+    pub struct Vec<T>(T);
+}
+"#,
+    );
+    db.set_crate_graph_from_fixture(crate_graph! {
+        "main": ("/main.rs", ["std"]),
+        "std": ("/std.rs", []),
+    });
+    assert_snapshot_matches!(db.diagnostics(), @r###"
+"read::<T>()?": `?` can't convert the error type `Vec<T>` into `MyError`
+"###
+    );
+}
+
+#[test]
+fn infer_for_loop_over_bounded_generic() {
+    let (mut db, pos) = MockDatabase::with_position(
+        r#"
+//- /main.rs
+use std::iter::IntoIterator;
+
+fn test<I: IntoIterator<Item = u8>>(items: I) {
+    for x in items {
+        x<|>;
+    }
+}
+
+//- /std.rs
+pub mod iter {
+    pub trait IntoIterator {
+        type Item;
+    }
+}
+"#,
+    );
+    db.set_crate_graph_from_fixture(crate_graph! {
+        "main": ("/main.rs", ["std"]),
+        "std": ("/std.rs", []),
+    });
+    assert_eq!("u8", type_at_pos(&db, pos));
+}
+
+#[test]
+fn fast_inference_skips_projections_through_bounds() {
+    let (mut db, pos) = MockDatabase::with_position(
+        r#"
+//- /main.rs
+use std::iter::IntoIterator;
+
+fn test<I: IntoIterator<Item = u8>>(items: I) {
+    for x in items {
+        x<|>;
+    }
+}
+
+//- /std.rs
+pub mod iter {
+    pub trait IntoIterator {
+        type Item;
+    }
+}
+"#,
+    );
+    db.set_crate_graph_from_fixture(crate_graph! {
+        "main": ("/main.rs", ["std"]),
+        "std": ("/std.rs", []),
+    });
+    let file = db.parse(pos.file_id);
+    let expr = algo::find_node_at_offset::<ast::Expr>(file.syntax(), pos.offset).unwrap();
+    let fn_def = expr.syntax().ancestors().find_map(ast::FnDef::cast).unwrap();
+    let module = source_binder::module_from_child_node(&db, pos.file_id, fn_def.syntax()).unwrap();
+    let def: DefWithBody = source_binder::function_from_module(&db, module, fn_def).into();
+    let expr_id = def.body_source_map(&db).node_expr(expr).unwrap();
+
+    let fast = def.infer_with_mode(&db, InferenceMode::Fast);
+    assert_eq!(fast[expr_id], Ty::Unknown);
+    let full = def.infer_with_mode(&db, InferenceMode::Full);
+    assert_eq!(full[expr_id].display(&db).to_string(), "u8");
+}
+
+#[test]
+fn infer_add_through_bound_output() {
+    let t = type_at(
+        r#"
+//- /main.rs
+#[lang = "add"]
+trait Add<Rhs = Self> {
+    type Output;
+    fn add(self, rhs: Rhs) -> Self::Output;
+}
+fn sum<T: Add<Output = T>>(a: T, b: T) -> T {
+    let c = a + b;
+    c<|>
+}
+"#,
+    );
+    assert_eq!(t, "T");
+}
+
+#[test]
+fn infer_builder_chain_keeps_type_param() {
+    let t = type_at(
+        r#"
+//- /main.rs
+struct X;
+struct B<T> { t: T }
+impl<T> B<T> {
+    fn new(t: T) -> B<T> { B { t } }
+    fn step(self) -> Self { self }
+    fn other_step(self) -> B<T> { self }
+}
+fn test() {
+    let b = B::new(X).step().other_step();
+    b<|>;
+}
+"#,
+    );
+    assert_eq!(t, "B<X>");
+}
+
+#[test]
+fn infer_enum_variant_leaves_other_params_open() {
+    let t = type_at(
+        r#"
+//- /main.rs
+enum Result<T, E> { Ok(T), Err(E) }
+fn test() {
+    let r = Result::Ok(5);
+    r<|>;
+}
+"#,
+    );
+    assert_eq!(t, "Result<i32, {unknown}>");
+    let t = type_at(
+        r#"
+//- /main.rs
+enum Result<T, E> { Ok(T), Err(E) }
+fn take(r: Result<i32, u8>) {}
+fn test() {
+    let r = Result::Ok(5);
+    take(r);
+    r<|>;
+}
+"#,
+    );
+    assert_eq!(t, "Result<i32, u8>");
+}
+
+#[test]
+fn infer_method_call_on_trait_object() {
+    let t = type_at(
+        r#"
+//- /main.rs
+enum Option<T> { Some(T), None }
+trait Iterator {
+    type Item;
+    fn next(&mut self) -> Option<Self::Item>;
+}
+fn test(iter: &mut dyn Iterator<Item = u32>) {
+    let x = iter.next();
+    x<|>;
+}
+"#,
+    );
+    assert_eq!(t, "Option<u32>");
+}
+
+#[test]
+fn infer_struct_update_takes_args_from_base() {
+    let (db, pos) = MockDatabase::with_position(
+        r#"
+//- /main.rs
+struct Foo<T> { x: T, y: T }
+fn make<T>() -> T {}
+fn test(base: Foo<i32>) {
+    let foo = Foo { x: make(<|>), ..base };
+    foo;
+}
+"#,
+    );
+    assert_eq!("i32", type_at_pos(&db, pos));
+    let t = type_at(
+        r#"
+//- /main.rs
+struct Foo<T> { x: T, y: T }
+fn make<T>() -> T {}
+fn test(base: Foo<i32>) {
+    let foo = Foo { x: make(), ..base };
+    foo<|>;
+}
+"#,
+    );
+    assert_eq!(t, "Foo<i32>");
+}
+
+#[test]
+fn infer_trait_method_returning_self() {
+    let t = type_at(
+        r#"
+//- /main.rs
+trait Make {
+    fn make(&self) -> Self;
+}
+struct Foo;
+impl Make for Foo {
+    fn make(&self) -> Self { Foo }
+}
+fn test(foo: Foo) {
+    let x = foo.make().make();
+    x<|>;
+}
+"#,
+    );
+    assert_eq!(t, "Foo");
+}
+
+#[test]
+fn infer_generic_fn_item_coerced_to_fn_ptr() {
+    let t = type_at(
+        r#"
+//- /main.rs
+fn generic_fn<T>(t: T) -> T { t }
+fn test() {
+    let f: fn(i32) -> i32 = generic_fn::<i32>;
+    f<|>;
+}
+"#,
+    );
+    assert_eq!(t, "fn(i32) -> i32");
+}
+
+#[test]
+fn type_param_resolves_in_nested_closures() {
+    let (db, pos) = MockDatabase::with_position(
+        r#"
+//- /main.rs
+fn test<T>(t: T) {
+    let f = || {
+        let g = || {
+            let h = || {
+                let x: <|>T = t;
+            };
+        };
+    };
+}
+"#,
+    );
+    let file = db.parse(pos.file_id);
+    let path = algo::find_node_at_offset::<ast::Path>(file.syntax(), pos.offset).unwrap();
+    let analyzer = SourceAnalyzer::new(&db, pos.file_id, path.syntax(), Some(pos.offset));
+    assert_eq!(analyzer.resolve_path(&db, path), Some(PathResolution::GenericParam(0)));
+}
+
+#[test]
+fn type_param_resolves_in_let_annotation() {
+    let (db, pos) = MockDatabase::with_position(
+        r#"
+//- /main.rs
+struct S<U>(U);
+impl<U> S<U> {
+    fn test<T>(&self, t: T) {
+        let y: <|>T = t;
+    }
+}
+"#,
+    );
+    let file = db.parse(pos.file_id);
+    let path = algo::find_node_at_offset::<ast::Path>(file.syntax(), pos.offset).unwrap();
+    let analyzer = SourceAnalyzer::new(&db, pos.file_id, path.syntax(), Some(pos.offset));
+    // the impl's `U` comes first
+    assert_eq!(analyzer.resolve_path(&db, path), Some(PathResolution::GenericParam(1)));
+}
+
+#[test]
+fn type_param_resolves_in_closure_param_annotation() {
+    let (db, pos) = MockDatabase::with_position(
+        r#"
+//- /main.rs
+fn test<T>(t: T) {
+    let f = |x: <|>T| x;
+}
+"#,
+    );
+    let file = db.parse(pos.file_id);
+    let path = algo::find_node_at_offset::<ast::Path>(file.syntax(), pos.offset).unwrap();
+    let analyzer = SourceAnalyzer::new(&db, pos.file_id, path.syntax(), Some(pos.offset));
+    assert_eq!(analyzer.resolve_path(&db, path), Some(PathResolution::GenericParam(0)));
+}
+
+#[test]
+fn infer_closure_param_with_type_param_annotation() {
+    let t = type_at(
+        r#"
+//- /main.rs
+struct S<U>(U);
+impl<U> S<U> {
+    fn test<T>(&self) {
+        let f = |x: T, y: U| { x<|>; y };
+    }
+}
+"#,
+    );
+    assert_eq!(t, "T");
+}
+
 fn type_at_pos(db: &MockDatabase, pos: FilePosition) -> String {
     let file = db.parse(pos.file_id);
     let expr = algo::find_node_at_offset::<ast::Expr>(file.syntax(), pos.offset).unwrap();
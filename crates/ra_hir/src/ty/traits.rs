@@ -4,8 +4,11 @@ use std::sync::{Arc, Mutex};
 use log::debug;
 use chalk_ir::cast::Cast;
 
-use crate::{Crate, Trait, db::HirDatabase, ImplBlock};
-use super::{TraitRef, Ty, Canonical};
+use crate::{
+    Crate, Trait, db::HirDatabase, ImplBlock, ImplItem, Name, generics::HasGenericParams,
+    nameres::Namespace,
+};
+use super::{TraitRef, Ty, Substs, Canonical};
 
 use self::chalk::{ToChalk, from_chalk};
 
@@ -45,6 +48,61 @@ pub(crate) fn impls_for_trait(
     impls.into()
 }
 
+/// Finds an impl of `trait_` whose self type matches `ty`, and returns the
+/// value of its associated type `name`.
+// FIXME: this should ask Chalk to normalize `<ty as trait_>::name` instead,
+// once it knows about associated types; where clauses on the impls are
+// ignored here.
+pub(crate) fn impl_assoc_type(
+    db: &impl HirDatabase,
+    krate: Crate,
+    trait_: Trait,
+    ty: &Ty,
+    name: &Name,
+) -> Option<Ty> {
+    for impl_block in db.impls_for_trait(krate, trait_).iter() {
+        let generics = impl_block.generic_params(db);
+        let mut substs = vec![None; generics.count_params_including_parent()];
+        if !match_ty(&impl_block.target_ty(db), ty, &mut substs) {
+            continue;
+        }
+        let substs: Substs =
+            substs.into_iter().map(|ty| ty.unwrap_or(Ty::Unknown)).collect::<Vec<_>>().into();
+        let assoc_type = impl_block.items(db).into_iter().find_map(|item| match item {
+            ImplItem::TypeAlias(t) if t.name(db).as_ref() == Some(name) => Some(t),
+            _ => None,
+        })?;
+        return Some(db.type_for_def(assoc_type.into(), Namespace::Types).subst(&substs));
+    }
+    None
+}
+
+/// Matches `ty` against the self type of an impl, filling in the values of
+/// the impl's type parameters into `substs`. Returns `false` if the types don't
+/// match.
+fn match_ty(pattern: &Ty, ty: &Ty, substs: &mut Vec<Option<Ty>>) -> bool {
+    match (pattern, ty) {
+        (Ty::Param { idx, .. }, _) => match substs.get_mut(*idx as usize) {
+            Some(Some(existing)) => *existing == *ty,
+            Some(slot) => {
+                *slot = Some(ty.clone());
+                true
+            }
+            None => false,
+        },
+        (Ty::Apply(pattern), Ty::Apply(ty)) => {
+            pattern.ctor == ty.ctor
+                && pattern.parameters.len() == ty.parameters.len()
+                && pattern
+                    .parameters
+                    .iter()
+                    .zip(ty.parameters.iter())
+                    .all(|(pattern, ty)| match_ty(pattern, ty, substs))
+        }
+        _ => false,
+    }
+}
+
 fn solve(
     db: &impl HirDatabase,
     krate: Crate,
@@ -12,6 +12,7 @@ use crate::{
     Trait, HasGenericParams, ImplBlock,
     db::HirDatabase,
     ty::{TraitRef, Ty, ApplicationTy, TypeCtor, Substs},
+    type_ref::TypeRef,
 };
 use super::ChalkContext;
 
@@ -146,6 +147,26 @@ impl ToChalk for ImplBlock {
     }
 }
 
+/// The where predicates of `impl_block` (including its inline bounds) as
+/// trait refs, or `None` if any of them can't be lowered, e.g. because the
+/// trait can't be resolved.
+fn impl_where_clauses(db: &impl HirDatabase, impl_block: ImplBlock) -> Option<Vec<TraitRef>> {
+    let resolver = impl_block.resolver(db);
+    let generic_params = impl_block.generic_params(db);
+    generic_params
+        .where_predicates
+        .iter()
+        .map(|pred| {
+            let self_ty = Ty::from_hir(db, &resolver, &pred.type_ref);
+            if self_ty == Ty::Unknown {
+                return None;
+            }
+            let trait_ref = TypeRef::Path(pred.trait_ref.clone());
+            TraitRef::from_hir(db, &resolver, &trait_ref, Some(self_ty))
+        })
+        .collect()
+}
+
 fn make_binders<T>(value: T, num_vars: usize) -> chalk_ir::Binders<T> {
     chalk_ir::Binders {
         value,
@@ -196,6 +217,19 @@ where
             TypeCtor::FnPtr { num_args } => (num_args as usize + 1, true),
             TypeCtor::Tuple { cardinality } => (cardinality as usize, true),
             TypeCtor::FnDef(_) => unimplemented!(),
+            TypeCtor::Dyn(trait_) => {
+                let num_args = trait_.generic_params(self.db).count_params_including_parent() - 1;
+                (num_args + trait_.trait_data(self.db).associated_types().count(), true)
+            }
+            TypeCtor::Opaque(type_alias) => {
+                (type_alias.generic_params(self.db).count_params_including_parent(), true)
+            }
+            TypeCtor::AssocType(type_alias) => match type_alias.parent_trait(self.db) {
+                Some(trait_) => {
+                    (trait_.generic_params(self.db).count_params_including_parent(), true)
+                }
+                None => (0, true),
+            },
             TypeCtor::Adt(adt) => {
                 let generic_params = adt.generic_params(self.db);
                 (
@@ -232,6 +266,14 @@ where
             .target_trait_ref(self.db)
             .expect("FIXME handle unresolved impl block trait ref")
             .subst(&bound_vars);
+        let where_clauses = impl_where_clauses(self.db, impl_block)
+            .unwrap_or_default()
+            .into_iter()
+            .map(|it| {
+                let trait_ref = it.subst(&bound_vars).to_chalk(self.db);
+                make_binders(chalk_ir::WhereClause::Implemented(trait_ref), 0)
+            })
+            .collect();
         let impl_type = if impl_block.module().krate(self.db) == Some(self.krate) {
             chalk_rust_ir::ImplType::Local
         } else {
@@ -240,7 +282,7 @@ where
         let impl_datum_bound = chalk_rust_ir::ImplDatumBound {
             // FIXME handle negative impls (impl !Sync for Foo)
             trait_ref: chalk_rust_ir::PolarizedTraitRef::Positive(trait_ref.to_chalk(self.db)),
-            where_clauses: Vec::new(),        // FIXME add where clauses
+            where_clauses,
             associated_ty_values: Vec::new(), // FIXME add associated type values
             impl_type,
         };
@@ -253,10 +295,10 @@ where
         self.db
             .impls_for_trait(self.krate, trait_)
             .iter()
-            // FIXME temporary hack -- as long as we're not lowering where clauses
-            // correctly, ignore impls with them completely so as to not treat
-            // impl<T> Trait for T where T: ... as a blanket impl on all types
-            .filter(|impl_block| impl_block.generic_params(self.db).where_predicates.is_empty())
+            // impls with where clauses that can't be lowered are ignored, so
+            // as to not treat impl<T> Trait for T where T: ... as a blanket
+            // impl on all types
+            .filter(|impl_block| impl_where_clauses(self.db, *impl_block).is_some())
             .map(|impl_block| impl_block.to_chalk(self.db))
             .collect()
     }
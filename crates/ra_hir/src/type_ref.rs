@@ -1,9 +1,12 @@
 //! HIR for references to types. Paths in these are not yet resolved. They can
 //! be directly created from an ast::TypeRef, without further queries.
 
-use ra_syntax::ast::{self, TypeAscriptionOwner};
+use std::{fmt, sync::Arc};
 
-use crate::Path;
+use rustc_hash::FxHashMap;
+use ra_syntax::ast::{self, TypeAscriptionOwner, TypeBoundsOwner};
+
+use crate::{Name, Path, path::GenericArg};
 
 #[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
 pub enum Mutability {
@@ -44,19 +47,23 @@ pub enum TypeRef {
     Path(Path),
     RawPtr(Box<TypeRef>, Mutability),
     Reference(Box<TypeRef>, Mutability),
-    Array(Box<TypeRef> /*, Expr*/),
+    /// An array type, with the length if it is a path like `N` in `[u8; N]`.
+    // FIXME: record other length expressions as well
+    Array(Box<TypeRef>, Option<Path>),
     Slice(Box<TypeRef>),
     /// A fn pointer. Last element of the vector is the return type.
     Fn(Vec<TypeRef>),
     // For
-    // ImplTrait,
-    // DynTrait,
+    /// An `impl Trait` type, with the paths of the traits in its bounds.
+    ImplTrait(Vec<Path>),
+    /// A trait object, with the paths of the traits in its bounds.
+    DynTrait(Vec<Path>),
     Error,
 }
 
 impl TypeRef {
     /// Converts an `ast::TypeRef` to a `hir::TypeRef`.
-    pub(crate) fn from_ast(node: &ast::TypeRef) -> Self {
+    pub fn from_ast(node: &ast::TypeRef) -> Self {
         use ra_syntax::ast::TypeRefKind::*;
         match node.kind() {
             ParenType(inner) => TypeRef::from_ast_opt(inner.type_ref()),
@@ -70,7 +77,14 @@ impl TypeRef {
                 let mutability = Mutability::from_mutable(inner.is_mut());
                 TypeRef::RawPtr(Box::new(inner_ty), mutability)
             }
-            ArrayType(inner) => TypeRef::Array(Box::new(TypeRef::from_ast_opt(inner.type_ref()))),
+            ArrayType(inner) => {
+                let inner_ty = TypeRef::from_ast_opt(inner.type_ref());
+                let len = match inner.expr().map(|it| it.kind()) {
+                    Some(ast::ExprKind::PathExpr(it)) => it.path().and_then(Path::from_ast),
+                    _ => None,
+                };
+                TypeRef::Array(Box::new(inner_ty), len)
+            }
             SliceType(inner) => TypeRef::Slice(Box::new(TypeRef::from_ast_opt(inner.type_ref()))),
             ReferenceType(inner) => {
                 let inner_ty = TypeRef::from_ast_opt(inner.type_ref());
@@ -90,8 +104,8 @@ impl TypeRef {
             }
             // for types are close enough for our purposes to the inner type for now...
             ForType(inner) => TypeRef::from_ast_opt(inner.type_ref()),
-            ImplTraitType(_inner) => TypeRef::Error,
-            DynTraitType(_inner) => TypeRef::Error,
+            ImplTraitType(inner) => TypeRef::ImplTrait(bound_paths(inner)),
+            DynTraitType(inner) => TypeRef::DynTrait(bound_paths(inner)),
         }
     }
 
@@ -106,4 +120,196 @@ impl TypeRef {
     pub fn unit() -> TypeRef {
         TypeRef::Tuple(Vec::new())
     }
+
+    /// Calls `f` for this type ref and all type refs nested in it, including
+    /// the generic args and associated type bindings of paths.
+    pub(crate) fn walk(&self, f: &mut impl FnMut(&TypeRef)) {
+        f(self);
+        match self {
+            TypeRef::Tuple(types) | TypeRef::Fn(types) => types.iter().for_each(|t| t.walk(f)),
+            TypeRef::RawPtr(inner, _)
+            | TypeRef::Reference(inner, _)
+            | TypeRef::Array(inner, _)
+            | TypeRef::Slice(inner) => inner.walk(f),
+            TypeRef::Path(path) => walk_path(path, f),
+            TypeRef::ImplTrait(paths) | TypeRef::DynTrait(paths) => {
+                paths.iter().for_each(|path| walk_path(path, f))
+            }
+            TypeRef::Never | TypeRef::Placeholder | TypeRef::Error => {}
+        }
+    }
+    /// Like `walk`, but allows modifying the type refs. `f` is called before
+    /// recursing into the (possibly modified) type ref.
+    pub(crate) fn walk_mut(&mut self, f: &mut impl FnMut(&mut TypeRef)) {
+        f(self);
+        match self {
+            TypeRef::Tuple(types) | TypeRef::Fn(types) => {
+                types.iter_mut().for_each(|t| t.walk_mut(f))
+            }
+            TypeRef::RawPtr(inner, _)
+            | TypeRef::Reference(inner, _)
+            | TypeRef::Array(inner, _)
+            | TypeRef::Slice(inner) => inner.walk_mut(f),
+            TypeRef::Path(path) => walk_path_mut(path, f),
+            TypeRef::ImplTrait(paths) | TypeRef::DynTrait(paths) => {
+                paths.iter_mut().for_each(|path| walk_path_mut(path, f))
+            }
+            TypeRef::Never | TypeRef::Placeholder | TypeRef::Error => {}
+        }
+    }
+}
+
+/// Calls `TypeRef::walk` for the generic args and associated type bindings of
+/// `path`.
+fn walk_path(path: &Path, f: &mut impl FnMut(&TypeRef)) {
+    for args in path.segments.iter().filter_map(|s| s.args_and_bindings.as_ref()) {
+        for arg in args.args.iter() {
+            match arg {
+                GenericArg::Type(type_ref) => type_ref.walk(f),
+            }
+        }
+        args.bindings.iter().for_each(|(_, type_ref)| type_ref.walk(f));
+    }
+}
+
+/// Calls `TypeRef::walk_mut` for the generic args and associated type
+/// bindings of `path`.
+pub(crate) fn walk_path_mut(path: &mut Path, f: &mut impl FnMut(&mut TypeRef)) {
+    for args in path.segments.iter_mut().filter_map(|s| s.args_and_bindings.as_mut()) {
+        let args = Arc::make_mut(args);
+        for arg in args.args.iter_mut() {
+            match arg {
+                GenericArg::Type(type_ref) => type_ref.walk_mut(f),
+            }
+        }
+        args.bindings.iter_mut().for_each(|(_, type_ref)| type_ref.walk_mut(f));
+    }
+}
+
+/// Replaces the types in `type_ref` that are just one of the names in
+/// `substs` by the corresponding type ref, like `Vec<T>` by `Vec<i32>` for
+/// `T = i32`. This works purely on the syntax level, so the names don't have
+/// to be type params, and the replacements aren't substituted again.
+pub fn substitute_named(type_ref: &TypeRef, substs: &FxHashMap<Name, TypeRef>) -> TypeRef {
+    let mut result = type_ref.clone();
+    substitute_named_in(&mut result, substs);
+    result
+}
+
+fn substitute_named_in(type_ref: &mut TypeRef, substs: &FxHashMap<Name, TypeRef>) {
+    let subst = match type_ref {
+        TypeRef::Path(path) => path.as_ident().and_then(|name| substs.get(name)),
+        _ => None,
+    };
+    if let Some(subst) = subst {
+        *type_ref = subst.clone();
+        return;
+    }
+    match type_ref {
+        TypeRef::Tuple(types) | TypeRef::Fn(types) => {
+            types.iter_mut().for_each(|t| substitute_named_in(t, substs))
+        }
+        TypeRef::RawPtr(inner, _)
+        | TypeRef::Reference(inner, _)
+        | TypeRef::Array(inner, _)
+        | TypeRef::Slice(inner) => substitute_named_in(inner, substs),
+        TypeRef::Path(path) => substitute_named_in_path(path, substs),
+        TypeRef::ImplTrait(paths) | TypeRef::DynTrait(paths) => {
+            paths.iter_mut().for_each(|path| substitute_named_in_path(path, substs))
+        }
+        TypeRef::Never | TypeRef::Placeholder | TypeRef::Error => {}
+    }
+}
+
+fn substitute_named_in_path(path: &mut Path, substs: &FxHashMap<Name, TypeRef>) {
+    for args in path.segments.iter_mut().filter_map(|s| s.args_and_bindings.as_mut()) {
+        let args = Arc::make_mut(args);
+        for arg in args.args.iter_mut() {
+            match arg {
+                GenericArg::Type(type_ref) => substitute_named_in(type_ref, substs),
+            }
+        }
+        args.bindings.iter_mut().for_each(|(_, type_ref)| substitute_named_in(type_ref, substs));
+    }
+}
+
+impl fmt::Display for TypeRef {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TypeRef::Never => write!(f, "!"),
+            TypeRef::Placeholder => write!(f, "_"),
+            TypeRef::Tuple(types) if types.len() == 1 => write!(f, "({},)", types[0]),
+            TypeRef::Tuple(types) => write!(f, "({})", join(types)),
+            TypeRef::Path(path) => write!(f, "{}", path),
+            TypeRef::RawPtr(inner, mutability) => {
+                write!(f, "*{}{}", mutability.as_keyword_for_ptr(), inner)
+            }
+            TypeRef::Reference(inner, mutability) => {
+                write!(f, "&{}{}", mutability.as_keyword_for_ref(), inner)
+            }
+            TypeRef::Array(inner, Some(len)) => write!(f, "[{}; {}]", inner, len),
+            TypeRef::Array(inner, None) => write!(f, "[{}; _]", inner),
+            TypeRef::Slice(inner) => write!(f, "[{}]", inner),
+            TypeRef::Fn(types) => match types.split_last() {
+                Some((ret, params)) => write!(f, "fn({}) -> {}", join(params), ret),
+                None => write!(f, "fn()"),
+            },
+            TypeRef::ImplTrait(paths) => {
+                let paths: Vec<String> = paths.iter().map(|it| it.to_string()).collect();
+                write!(f, "impl {}", paths.join(" + "))
+            }
+            TypeRef::DynTrait(paths) => {
+                let paths: Vec<String> = paths.iter().map(|it| it.to_string()).collect();
+                write!(f, "dyn {}", paths.join(" + "))
+            }
+            TypeRef::Error => write!(f, "{{error}}"),
+        }
+    }
+}
+
+/// The paths of the traits in the bounds of `impl Trait` or `dyn Trait`;
+/// lifetime bounds are skipped.
+fn bound_paths(node: &impl ast::TypeBoundsOwner) -> Vec<Path> {
+    node.type_bound_list()
+        .into_iter()
+        .flat_map(|it| it.bounds())
+        .filter_map(|it| match it.type_ref()?.kind() {
+            ast::TypeRefKind::PathType(it) => Path::from_ast(it.path()?),
+            _ => None,
+        })
+        .collect()
+}
+
+fn join(types: &[TypeRef]) -> String {
+    types.iter().map(|t| t.to_string()).collect::<Vec<_>>().join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use ra_syntax::{SourceFile, ast::AstNode};
+
+    use crate::AsName;
+
+    use super::*;
+
+    #[test]
+    fn substitute_named_replaces_param() {
+        let file = SourceFile::parse("type X = Vec<T>; type Y = i32;");
+        let type_refs: Vec<TypeRef> = file
+            .syntax()
+            .descendants()
+            .filter_map(ast::TypeAliasDef::cast)
+            .map(|it| TypeRef::from_ast_opt(it.type_ref()))
+            .collect();
+        let param = file
+            .syntax()
+            .descendants()
+            .filter_map(ast::NameRef::cast)
+            .find(|it| it.text().as_str() == "T")
+            .unwrap()
+            .as_name();
+        let mut substs = FxHashMap::default();
+        substs.insert(param, type_refs[1].clone());
+        assert_eq!(substitute_named(&type_refs[0], &substs).to_string(), "Vec<i32>");
+    }
 }
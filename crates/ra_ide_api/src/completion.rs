@@ -11,6 +11,7 @@ mod complete_snippet;
 mod complete_path;
 mod complete_scope;
 mod complete_postfix;
+mod complete_lifetime;
 
 use ra_db::SourceDatabase;
 
@@ -24,10 +25,20 @@ use crate::{
 
 };
 #[cfg(test)]
-use crate::completion::completion_item::{do_completion, check_completion};
+use crate::completion::completion_item::{
+    do_completion, check_completion, check_completion_with_config,
+};
 
 pub use crate::completion::completion_item::{CompletionItem, CompletionItemKind, InsertTextFormat};
 
+/// The capabilities of the client that influence the completions.
+#[derive(Debug, Clone, Default)]
+pub struct CompletionConfig {
+    /// Whether the client supports snippets with placeholders, like the type
+    /// args in `foo::<${1:T}>()`.
+    pub snippet_placeholders: bool,
+}
+
 /// Main entry point for completion. We run completion as a two-phase process.
 ///
 /// First, we look at the position and collect a so-called `CompletionContext.
@@ -50,9 +61,13 @@ pub use crate::completion::completion_item::{CompletionItem, CompletionItemKind,
 /// `foo` *should* be present among the completion variants. Filtering by
 /// identifier prefix/fuzzy match should be done higher in the stack, together
 /// with ordering of completions (currently this is done by the client).
-pub(crate) fn completions(db: &db::RootDatabase, position: FilePosition) -> Option<Completions> {
+pub(crate) fn completions(
+    db: &db::RootDatabase,
+    position: FilePosition,
+    config: &CompletionConfig,
+) -> Option<Completions> {
     let original_file = db.parse(position.file_id);
-    let ctx = CompletionContext::new(db, &original_file, position)?;
+    let ctx = CompletionContext::new(db, &original_file, position, config)?;
 
     let mut acc = Completions::default();
 
@@ -67,5 +82,6 @@ pub(crate) fn completions(db: &db::RootDatabase, position: FilePosition) -> Opti
     complete_struct_literal::complete_struct_literal(&mut acc, &ctx);
     complete_pattern::complete_pattern(&mut acc, &ctx);
     complete_postfix::complete_postfix(&mut acc, &ctx);
+    complete_lifetime::complete_lifetime(&mut acc, &ctx);
     Some(acc)
 }
@@ -15,7 +15,7 @@ pub(super) fn complete_dot(acc: &mut Completions, ctx: &CompletionContext) {
 }
 
 fn complete_fields(acc: &mut Completions, ctx: &CompletionContext, receiver: Ty) {
-    for receiver in receiver.autoderef(ctx.db) {
+    for receiver in ctx.analyzer.autoderef(ctx.db, receiver) {
         match receiver {
             Ty::Apply(a_ty) => match a_ty.ctor {
                 TypeCtor::Adt(AdtDef::Struct(s)) => {
@@ -39,7 +39,8 @@ fn complete_fields(acc: &mut Completions, ctx: &CompletionContext, receiver: Ty)
 fn complete_methods(acc: &mut Completions, ctx: &CompletionContext, receiver: Ty) {
     ctx.analyzer.iterate_method_candidates(ctx.db, receiver, None, |_ty, func| {
         let sig = func.signature(ctx.db);
-        if sig.has_self_param() {
+        let is_visible = ctx.module.map_or(true, |module| func.is_visible_from(ctx.db, module));
+        if sig.has_self_param() && is_visible {
             acc.add_function(ctx, func);
         }
         None::<()>
@@ -221,6 +222,79 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_no_invisible_method_completion() {
+        assert_debug_snapshot_matches!(
+            do_ref_completion(
+                r"
+            mod m {
+                pub struct S;
+                impl S {
+                    fn private_method(&self) {}
+                    pub fn public_method(&self) {}
+                }
+            }
+            fn foo(s: m::S) {
+               s.<|>
+            }
+            ",
+            ),
+            @r###"[
+    CompletionItem {
+        label: "public_method",
+        source_range: [254; 254),
+        delete: [254; 254),
+        insert: "public_method()$0",
+        kind: Method,
+        detail: "pub fn public_method(&self)"
+    }
+]"###
+        );
+    }
+
+    #[test]
+    fn test_bounded_param_method_completion() {
+        assert_debug_snapshot_matches!(
+            do_ref_completion(
+                r"
+            mod m {
+                pub trait Tr { fn the_method(&self); }
+            }
+            fn foo<T: m::Tr>(t: T) {
+               t.<|>
+            }
+            ",
+            ),
+            @r###"[
+    CompletionItem {
+        label: "the_method",
+        source_range: [144; 144),
+        delete: [144; 144),
+        insert: "the_method()$0",
+        kind: Method,
+        detail: "fn the_method(&self)"
+    }
+]"###
+        );
+    }
+
+    #[test]
+    fn test_no_bounded_param_method_of_invisible_trait() {
+        assert_debug_snapshot_matches!(
+            do_ref_completion(
+                r"
+            mod m {
+                trait Tr { fn the_method(&self); }
+            }
+            fn foo<T: m::Tr>(t: T) {
+               t.<|>
+            }
+            ",
+            ),
+            @"[]"
+        );
+    }
+
     #[test]
     fn test_no_non_self_method() {
         assert_debug_snapshot_matches!(
@@ -0,0 +1,34 @@
+use ra_syntax::SyntaxKind::LIFETIME;
+
+use crate::completion::{
+    CompletionContext, Completions, CompletionKind, CompletionItem, CompletionItemKind,
+};
+
+/// Complete the lifetime params in scope in the lifetime position of a
+/// reference type, i.e. `fn f<'a>(x: &<|>)`.
+pub(super) fn complete_lifetime(acc: &mut Completions, ctx: &CompletionContext) {
+    if !ctx.is_lifetime_position {
+        return;
+    }
+    // in `&'a<|>`, the lifetime that is already there is replaced
+    let source_range =
+        if ctx.token.kind() == LIFETIME { ctx.token.range() } else { ctx.source_range() };
+    for name in ctx.analyzer.lifetimes_in_scope() {
+        CompletionItem::new(CompletionKind::Reference, source_range, name.to_string())
+            .kind(CompletionItemKind::TypeParam)
+            .add_to(acc);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::completion::{do_completion, CompletionKind};
+
+    #[test]
+    fn completes_lifetime_params_in_reference_type() {
+        let completions = do_completion(r"fn f<'a, T>(x: &<|>) {}", CompletionKind::Reference);
+        let lifetimes: Vec<_> =
+            completions.iter().map(|it| it.label()).filter(|it| it.starts_with('\'')).collect();
+        assert_eq!(lifetimes, vec!["'a"]);
+    }
+}
@@ -7,13 +7,14 @@ use ra_syntax::{
 };
 use hir::source_binder;
 
-use crate::{db, FilePosition};
+use crate::{db, FilePosition, completion::CompletionConfig};
 
 /// `CompletionContext` is created early during completion to figure out, where
 /// exactly is the cursor, syntax-wise.
 #[derive(Debug)]
 pub(crate) struct CompletionContext<'a> {
     pub(super) db: &'a db::RootDatabase,
+    pub(super) config: &'a CompletionConfig,
     pub(super) analyzer: hir::SourceAnalyzer,
     pub(super) offset: TextUnit,
     pub(super) token: SyntaxToken<'a>,
@@ -38,6 +39,8 @@ pub(crate) struct CompletionContext<'a> {
     pub(super) dot_receiver: Option<&'a ast::Expr>,
     /// If this is a call (method or function) in particular, i.e. the () are already there.
     pub(super) is_call: bool,
+    /// The lifetime position of a reference type, i.e. `&<|>` or `&'a<|>`.
+    pub(super) is_lifetime_position: bool,
 }
 
 impl<'a> CompletionContext<'a> {
@@ -45,6 +48,7 @@ impl<'a> CompletionContext<'a> {
         db: &'a db::RootDatabase,
         original_file: &'a SourceFile,
         position: FilePosition,
+        config: &'a CompletionConfig,
     ) -> Option<CompletionContext<'a>> {
         let module = source_binder::module_from_position(db, position);
         let token = find_token_at_offset(original_file.syntax(), position.offset).left_biased()?;
@@ -52,6 +56,7 @@ impl<'a> CompletionContext<'a> {
             hir::SourceAnalyzer::new(db, position.file_id, token.parent(), Some(position.offset));
         let mut ctx = CompletionContext {
             db,
+            config,
             analyzer,
             token,
             offset: position.offset,
@@ -68,6 +73,7 @@ impl<'a> CompletionContext<'a> {
             is_new_item: false,
             dot_receiver: None,
             is_call: false,
+            is_lifetime_position: false,
         };
         ctx.fill(original_file, position.offset);
         Some(ctx)
@@ -83,6 +89,11 @@ impl<'a> CompletionContext<'a> {
     }
 
     fn fill(&mut self, original_file: &'a SourceFile, offset: TextUnit) {
+        self.is_lifetime_position = match self.token.kind() {
+            AMP | LIFETIME => self.token.parent().kind() == REFERENCE_TYPE,
+            _ => false,
+        };
+
         // Insert a fake ident to get a valid parse tree. We will use this file
         // to determine context, though the original_file will be used for
         // actual completion.
@@ -4,6 +4,9 @@ use hir::Documentation;
 use ra_syntax::TextRange;
 use ra_text_edit::{TextEditBuilder, TextEdit};
 
+#[cfg(test)]
+use crate::completion::CompletionConfig;
+
 /// `CompletionItem` describes a single completion variant in the editor pop-up.
 /// It is basically a POD with various properties. To construct a
 /// `CompletionItem`, use `new` method and the `Builder` struct.
@@ -283,6 +286,15 @@ impl Into<Vec<CompletionItem>> for Completions {
 
 #[cfg(test)]
 pub(crate) fn do_completion(code: &str, kind: CompletionKind) -> Vec<CompletionItem> {
+    do_completion_with_config(code, kind, &CompletionConfig::default())
+}
+
+#[cfg(test)]
+pub(crate) fn do_completion_with_config(
+    code: &str,
+    kind: CompletionKind,
+    config: &CompletionConfig,
+) -> Vec<CompletionItem> {
     use crate::mock_analysis::{single_file_with_position, analysis_and_position};
     use crate::completion::completions;
     let (analysis, position) = if code.contains("//-") {
@@ -290,7 +302,7 @@ pub(crate) fn do_completion(code: &str, kind: CompletionKind) -> Vec<CompletionI
     } else {
         single_file_with_position(code)
     };
-    let completions = completions(&analysis.db, position).unwrap();
+    let completions = completions(&analysis.db, position, config).unwrap();
     let completion_items: Vec<CompletionItem> = completions.into();
     let mut kind_completions: Vec<CompletionItem> =
         completion_items.into_iter().filter(|c| c.completion_kind == kind).collect();
@@ -304,3 +316,15 @@ pub(crate) fn check_completion(test_name: &str, code: &str, kind: CompletionKind
     let kind_completions = do_completion(code, kind);
     assert_debug_snapshot_matches!(test_name, kind_completions);
 }
+
+#[cfg(test)]
+pub(crate) fn check_completion_with_config(
+    test_name: &str,
+    code: &str,
+    kind: CompletionKind,
+    config: &CompletionConfig,
+) {
+    use insta::assert_debug_snapshot_matches;
+    let kind_completions = do_completion_with_config(code, kind, config);
+    assert_debug_snapshot_matches!(test_name, kind_completions);
+}
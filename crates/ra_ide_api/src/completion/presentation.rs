@@ -1,7 +1,7 @@
 //! This modules takes care of rendering various defenitions as completion items.
 use join_to_string::join;
 use test_utils::tested_by;
-use hir::{Docs, PerNs, Resolution, HirDisplay};
+use hir::{Docs, PerNs, Resolution, HirDisplay, HasGenericParams};
 use ra_syntax::ast::NameOwner;
 
 use crate::completion::{
@@ -108,11 +108,16 @@ impl Completions {
         // If not an import, add parenthesis automatically.
         if ctx.use_item_syntax.is_none() && !ctx.is_call {
             tested_by!(inserts_parens_for_function_calls);
+            let turbofish = if ctx.config.snippet_placeholders {
+                turbofish_snippet(ctx, func)
+            } else {
+                String::new()
+            };
             let snippet =
                 if sig.params().is_empty() || sig.has_self_param() && sig.params().len() == 1 {
-                    format!("{}()$0", sig.name())
+                    format!("{}{}()$0", sig.name(), turbofish)
                 } else {
-                    format!("{}($0)", sig.name())
+                    format!("{}{}($0)", sig.name(), turbofish)
                 };
             builder = builder.insert_snippet(snippet);
         }
@@ -170,11 +175,27 @@ impl Completions {
     }
 }
 
+/// The generic args of a call to `func` as a snippet with one tab stop per
+/// type param, like `::<${1:T}>`, or nothing if the arguments of the call
+/// determine all of its own type params.
+fn turbofish_snippet(ctx: &CompletionContext, func: hir::Function) -> String {
+    if hir::GenericDef::from(func).uninferrable_params(ctx.db).is_empty() {
+        return String::new();
+    }
+    let generics = func.generic_params(ctx.db);
+    let params = &generics.params_including_parent()[generics.count_parent_params()..];
+    let tab_stops =
+        params.iter().enumerate().map(|(idx, param)| format!("${{{}:{}}}", idx + 1, param.name()));
+    join(tab_stops).separator(", ").surround_with("::<", ">").to_string()
+}
+
 #[cfg(test)]
 mod tests {
     use test_utils::covers;
 
-    use crate::completion::{CompletionKind, check_completion};
+    use crate::completion::{
+        CompletionKind, CompletionConfig, check_completion, check_completion_with_config,
+    };
 
     fn check_reference_completion(code: &str, expected_completions: &str) {
         check_completion(code, expected_completions, CompletionKind::Reference);
@@ -211,6 +232,20 @@ mod tests {
         )
     }
 
+    #[test]
+    fn inserts_turbofish_for_generic_functions() {
+        check_completion_with_config(
+            "inserts_turbofish_for_generic_functions",
+            r"
+            fn generic<T, U>(t: T) {}
+            fn inferable<T>(t: T) {}
+            fn main() { gen<|> }
+            ",
+            CompletionKind::Reference,
+            &CompletionConfig { snippet_placeholders: true },
+        );
+    }
+
     #[test]
     fn dont_render_function_parens_in_use_item() {
         check_reference_completion(
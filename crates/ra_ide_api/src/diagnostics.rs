@@ -70,6 +70,14 @@ pub(crate) fn diagnostics(db: &RootDatabase, file_id: FileId) -> Vec<Diagnostic>
             severity: Severity::Error,
             fix: Some(fix),
         })
+    })
+    .on::<hir::diagnostics::UninferrableParam, _>(|d| {
+        res.borrow_mut().push(Diagnostic {
+            range: d.highlight_range(),
+            message: d.message(),
+            severity: Severity::WeakWarning,
+            fix: None,
+        })
     });
     if let Some(m) = source_binder::module_from_file_id(db, file_id) {
         m.diagnostics(db, &mut sink);
@@ -336,6 +344,21 @@ mod tests {
 ]"####);
     }
 
+    #[test]
+    fn test_uninferrable_param_is_a_weak_warning() {
+        let (analysis, file_id) = single_file("fn make<T>() -> T { loop {} }");
+        let diagnostics = analysis.diagnostics(file_id).unwrap();
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(
+            diagnostics[0].message,
+            "the type param `T` can't be inferred from the arguments"
+        );
+        match diagnostics[0].severity {
+            Severity::WeakWarning => {}
+            severity => panic!("expected a weak warning, got {:?}", severity),
+        }
+    }
+
     #[test]
     fn test_check_unnecessary_braces_in_use_statement() {
         check_not_applicable(
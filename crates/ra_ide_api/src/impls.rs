@@ -176,4 +176,19 @@ mod tests {
             &["impl IMPL_BLOCK FileId(2) [0; 31)", "impl IMPL_BLOCK FileId(3) [0; 31)"],
         );
     }
+
+    #[test]
+    fn goto_implementation_for_generic_trait() {
+        check_goto(
+            "
+            //- /lib.rs
+            trait T<|><U> {}
+            struct Foo;
+            struct Bar<U>(U);
+            impl T<u32> for Foo {}
+            impl<U> T<U> for Bar<U> {}
+            ",
+            &["impl IMPL_BLOCK FileId(1) [44; 66)", "impl IMPL_BLOCK FileId(1) [67; 93)"],
+        );
+    }
 }
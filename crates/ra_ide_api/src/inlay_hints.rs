@@ -0,0 +1,249 @@
+use hir::{CallableDef, HasGenericParams, HirDisplay, Ty, TypeCtor};
+use ra_syntax::{
+    TextRange,
+    ast::{self, AstNode},
+};
+use ra_db::SourceDatabase;
+
+use crate::{FileId, db::RootDatabase};
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum InlayKind {
+    /// The inferred generic args of a call without turbofish, shown after
+    /// the path of the callee, or after the type for the args of the impl of
+    /// a method, like `::<String>` after `Vec` in `Vec::new`.
+    GenericArgs,
+    /// The inferred signature of a closure passed as an arg, shown on its
+    /// param list, like `|i32| -> i32` for `|x| x + 1`.
+    ClosureSignature,
+}
+
+#[derive(Debug)]
+pub struct InlayHint {
+    pub range: TextRange,
+    pub kind: InlayKind,
+    pub label: String,
+}
+
+pub(crate) fn inlay_hints(db: &RootDatabase, file_id: FileId) -> Vec<InlayHint> {
+    let source_file = db.parse(file_id);
+    let mut hints = Vec::new();
+    for call in source_file.syntax().descendants().filter_map(ast::CallExpr::cast) {
+        hints.extend(generic_args_hints(db, file_id, call));
+    }
+    // the arg lists of both function and method calls
+    for arg_list in source_file.syntax().descendants().filter_map(ast::ArgList::cast) {
+        let closures = arg_list.args().filter_map(|arg| match arg.kind() {
+            ast::ExprKind::LambdaExpr(it) => Some(it),
+            _ => None,
+        });
+        hints.extend(closures.filter_map(|closure| closure_signature_hint(db, file_id, closure)));
+    }
+    hints
+}
+
+/// The hints for the inferred generic args of `call`. For methods of impls,
+/// the args of the impl's type are shown after the type, and only the own
+/// args of the method after the method, like `Vec::<String>::new`.
+fn generic_args_hints(db: &RootDatabase, file_id: FileId, call: &ast::CallExpr) -> Vec<InlayHint> {
+    let mut hints = Vec::new();
+    let callee = match call.expr() {
+        Some(it) => it,
+        None => return hints,
+    };
+    let path = match callee.kind() {
+        ast::ExprKind::PathExpr(it) => match it.path() {
+            Some(it) => it,
+            None => return hints,
+        },
+        _ => return hints,
+    };
+    let analyzer = hir::SourceAnalyzer::new(db, file_id, call.syntax(), None);
+    let (def, args) = match analyzer.type_of(db, callee) {
+        Some(Ty::Apply(a_ty)) => match a_ty.ctor {
+            TypeCtor::FnDef(def) => (def, a_ty.parameters),
+            _ => return hints,
+        },
+        _ => return hints,
+    };
+    let (self_args, own_args) = match def {
+        CallableDef::Function(func) => {
+            let parent_count = func.generic_params(db).count_parent_params();
+            let self_args = func.impl_block(db).and_then(|impl_block| {
+                match impl_block.target_ty(db).subst(&args.prefix(parent_count)) {
+                    Ty::Apply(a_ty) => Some(a_ty.parameters.to_vec()),
+                    _ => None,
+                }
+            });
+            (self_args, args[parent_count..].to_vec())
+        }
+        CallableDef::Struct(_) | CallableDef::EnumVariant(_) => (None, args.to_vec()),
+    };
+    // the args are given explicitly, like in `Vec::<String>::new()`
+    let has_type_args =
+        |path: &ast::Path| path.segment().and_then(|it| it.type_arg_list()).is_some();
+    if let (Some(self_args), Some(qualifier)) = (self_args, path.qualifier()) {
+        if !has_type_args(qualifier) {
+            hints.extend(generic_args_hint(db, qualifier, &self_args));
+        }
+    }
+    if !has_type_args(path) {
+        hints.extend(generic_args_hint(db, path, &own_args));
+    }
+    hints
+}
+
+fn generic_args_hint(db: &RootDatabase, path: &ast::Path, args: &[Ty]) -> Option<InlayHint> {
+    // only show what was actually inferred
+    if args.is_empty() || args.iter().any(|it| *it == Ty::Unknown) {
+        return None;
+    }
+    let args: Vec<String> = args.iter().map(|it| it.display(db).to_string()).collect();
+    Some(InlayHint {
+        range: path.syntax().range(),
+        kind: InlayKind::GenericArgs,
+        label: format!("::<{}>", args.join(", ")),
+    })
+}
+
+fn closure_signature_hint(
+    db: &RootDatabase,
+    file_id: FileId,
+    closure: &ast::LambdaExpr,
+) -> Option<InlayHint> {
+    let param_list = closure.param_list()?;
+    let analyzer = hir::SourceAnalyzer::new(db, file_id, closure.syntax(), None);
+    let mut params = Vec::new();
+    for param in param_list.params() {
+        params.push(analyzer.type_of_pat(db, param.pat()?)?);
+    }
+    let ret = analyzer.type_of(db, closure.body()?)?;
+    // only show what was actually inferred
+    if params.iter().chain(Some(&ret)).any(|it| *it == Ty::Unknown) {
+        return None;
+    }
+    let params: Vec<String> = params.iter().map(|it| it.display(db).to_string()).collect();
+    Some(InlayHint {
+        range: param_list.syntax().range(),
+        kind: InlayKind::ClosureSignature,
+        label: format!("|{}| -> {}", params.join(", "), ret.display(db)),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::mock_analysis::single_file;
+
+    use super::*;
+
+    #[test]
+    fn generic_args_of_call_without_turbofish() {
+        let (analysis, file_id) = single_file(
+            "
+            struct String;
+            struct Vec<T>(T);
+            impl<T> Vec<T> {
+                fn new() -> Vec<T> { loop {} }
+                fn push(&mut self, item: T) {}
+            }
+            fn main() {
+                let mut v = Vec::new();
+                v.push(String);
+            }
+            ",
+        );
+        let hints = analysis.inlay_hints(file_id).unwrap();
+        assert_eq!(hints.len(), 1);
+        assert_eq!(hints[0].kind, InlayKind::GenericArgs);
+        assert_eq!(hints[0].label, "::<String>");
+        let text = analysis.file_text(file_id);
+        let range = hints[0].range;
+        assert_eq!(&text[range.start().to_usize()..range.end().to_usize()], "Vec");
+    }
+
+    #[test]
+    fn generic_args_of_impl_and_method_are_hinted_separately() {
+        let (analysis, file_id) = single_file(
+            "
+            struct String;
+            struct Vec<T>(T);
+            impl<T> Vec<T> {
+                fn with<U>(u: U) -> Vec<T> { loop {} }
+                fn push(&mut self, item: T) {}
+            }
+            fn main() {
+                let mut v = Vec::with(1u8);
+                v.push(String);
+            }
+            ",
+        );
+        let hints = analysis.inlay_hints(file_id).unwrap();
+        let text = analysis.file_text(file_id);
+        let hints: Vec<(&str, &str)> = hints
+            .iter()
+            .map(|it| {
+                (&text[it.range.start().to_usize()..it.range.end().to_usize()], it.label.as_str())
+            })
+            .collect();
+        assert_eq!(hints, vec![("Vec", "::<String>"), ("Vec::with", "::<u8>")]);
+    }
+
+    #[test]
+    fn signature_of_closure_passed_for_fn_bound() {
+        let (analysis, file_id) = single_file(
+            "
+            trait Fn<Args> {}
+            fn apply<F: Fn(i32) -> i32>(f: F) -> i32 { f(1) }
+            fn main() {
+                apply(|x| x + 1);
+            }
+            ",
+        );
+        let hints = analysis.inlay_hints(file_id).unwrap();
+        assert_eq!(hints.len(), 1);
+        assert_eq!(hints[0].kind, InlayKind::ClosureSignature);
+        assert_eq!(hints[0].label, "|i32| -> i32");
+        let text = analysis.file_text(file_id);
+        let range = hints[0].range;
+        assert_eq!(&text[range.start().to_usize()..range.end().to_usize()], "|x|");
+    }
+
+    #[test]
+    fn signature_of_closure_passed_to_method_for_fn_bound() {
+        let (analysis, file_id) = single_file(
+            "
+            trait Fn<Args> {}
+            struct S;
+            impl S {
+                fn apply<F: Fn(i32) -> i32>(&self, f: F) -> i32 { f(1) }
+            }
+            fn main() {
+                S.apply(|x| x + 1);
+            }
+            ",
+        );
+        let hints = analysis.inlay_hints(file_id).unwrap();
+        assert_eq!(hints.len(), 1);
+        assert_eq!(hints[0].kind, InlayKind::ClosureSignature);
+        assert_eq!(hints[0].label, "|i32| -> i32");
+        let text = analysis.file_text(file_id);
+        let range = hints[0].range;
+        assert_eq!(&text[range.start().to_usize()..range.end().to_usize()], "|x|");
+    }
+
+    #[test]
+    fn no_generic_args_hint_with_turbofish() {
+        let (analysis, file_id) = single_file(
+            "
+            struct Vec<T>(T);
+            impl<T> Vec<T> {
+                fn new() -> Vec<T> { loop {} }
+            }
+            fn main() {
+                let v = Vec::<u32>::new();
+            }
+            ",
+        );
+        assert!(analysis.inlay_hints(file_id).unwrap().is_empty());
+    }
+}
@@ -1,7 +1,7 @@
 use itertools::Itertools;
 use ra_syntax::{
     SourceFile, TextRange, TextUnit, SyntaxNode, SyntaxElement, SyntaxToken,
-    SyntaxKind::{self, WHITESPACE, COMMA, R_CURLY, R_PAREN, R_BRACK},
+    SyntaxKind::{self, WHITESPACE, COMMA, R_CURLY, R_PAREN, R_BRACK, WHERE_PRED},
     algo::{find_covering_element, non_trivia_sibling},
     ast::{self, AstNode, AstToken},
     Direction,
@@ -100,6 +100,10 @@ fn remove_newline(edit: &mut TextEditBuilder, token: SyntaxToken, offset: TextUn
             TextRange::from_to(prev.range().start(), token.range().end()),
             space.to_string(),
         );
+    } else if prev.kind() == WHERE_PRED && next.kind() == WHERE_PRED {
+        // Adds the missing comma between two where predicates, so that they
+        // don't get merged into one: `T: Foo U: Bar` would be invalid
+        edit.replace(token.range(), ", ".to_string());
     } else if let (Some(_), Some(next)) =
         (prev.as_token().and_then(ast::Comment::cast), next.as_token().and_then(ast::Comment::cast))
     {
@@ -579,4 +583,42 @@ pub fn handle_find_matching_brace() {
 }",
         );
     }
+
+    #[test]
+    fn test_join_lines_where_predicates() {
+        check_join_lines_sel(
+            r"
+fn foo<T, U>()
+where
+    <|>T: Clone
+    U: Copy<|>
+{
+}
+",
+            r"
+fn foo<T, U>()
+where
+    T: Clone, U: Copy
+{
+}
+",
+        );
+        check_join_lines_sel(
+            r"
+fn foo<T, U>()
+where
+    <|>T: Clone,
+    U: Copy,<|>
+{
+}
+",
+            r"
+fn foo<T, U>()
+where
+    T: Clone, U: Copy,
+{
+}
+",
+        );
+    }
 }
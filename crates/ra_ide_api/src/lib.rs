@@ -24,6 +24,7 @@ mod extend_selection;
 mod hover;
 mod call_info;
 mod syntax_highlighting;
+mod inlay_hints;
 mod parent_module;
 mod references;
 mod impls;
@@ -60,7 +61,7 @@ use crate::{
 
 pub use crate::{
     change::{AnalysisChange, LibraryData},
-    completion::{CompletionItem, CompletionItemKind, InsertTextFormat},
+    completion::{CompletionItem, CompletionItemKind, InsertTextFormat, CompletionConfig},
     runnables::{Runnable, RunnableKind},
     references::ReferenceSearchResult,
     assists::{Assist, AssistId},
@@ -69,6 +70,7 @@ pub use crate::{
     line_index_utils::translate_offset_with_edit,
     folding_ranges::{Fold, FoldKind},
     syntax_highlighting::HighlightedRange,
+    inlay_hints::{InlayHint, InlayKind},
     diagnostics::Severity,
     display::{FunctionSignature, NavigationTarget, StructureNode, file_structure},
 };
@@ -467,9 +469,19 @@ impl Analysis {
         self.with_db(|db| syntax_highlighting::highlight(db, file_id))
     }
 
+    /// Computes the inlay hints for the given file, like the inferred generic
+    /// args of calls.
+    pub fn inlay_hints(&self, file_id: FileId) -> Cancelable<Vec<InlayHint>> {
+        self.with_db(|db| inlay_hints::inlay_hints(db, file_id))
+    }
+
     /// Computes completions at the given position.
-    pub fn completions(&self, position: FilePosition) -> Cancelable<Option<Vec<CompletionItem>>> {
-        self.with_db(|db| completion::completions(db, position).map(Into::into))
+    pub fn completions(
+        &self,
+        position: FilePosition,
+        config: &CompletionConfig,
+    ) -> Cancelable<Option<Vec<CompletionItem>>> {
+        self.with_db(|db| completion::completions(db, position, config).map(Into::into))
     }
 
     /// Computes assists (aks code actons aka intentions) for the given
@@ -1,9 +1,10 @@
 use itertools::Itertools;
 use ra_syntax::{
     TextRange, SyntaxNode,
-    ast::{self, AstNode, NameOwner, ModuleItemOwner, AttrsOwner},
+    ast::{self, AstNode, NameOwner, ModuleItemOwner, AttrsOwner, DocCommentsOwner},
 };
 use ra_db::SourceDatabase;
+use hir::HasGenericParams;
 
 use crate::{db::RootDatabase, FileId};
 
@@ -15,9 +16,15 @@ pub struct Runnable {
 
 #[derive(Debug)]
 pub enum RunnableKind {
-    Test { name: String },
+    /// `generics` is the generic param list of the test function (like
+    /// `<T>`), for display.
+    Test { name: String, generics: Option<String> },
     TestMod { path: String },
-    Bench { name: String },
+    Bench { name: String, generics: Option<String> },
+    /// The doctests in the docs of the function `name`, with the params of
+    /// that function as `generics`. For methods, `name` is qualified by the
+    /// impl's type, like `Foo::bar`, as in the names rustdoc gives the tests.
+    DocTest { name: String, generics: Option<String> },
     Bin,
 }
 
@@ -28,7 +35,7 @@ pub(crate) fn runnables(db: &RootDatabase, file_id: FileId) -> Vec<Runnable> {
 
 fn runnable(db: &RootDatabase, file_id: FileId, item: &SyntaxNode) -> Option<Runnable> {
     if let Some(fn_def) = ast::FnDef::cast(item) {
-        runnable_fn(fn_def)
+        runnable_fn(db, file_id, fn_def)
     } else if let Some(m) = ast::Module::cast(item) {
         runnable_mod(db, file_id, m)
     } else {
@@ -36,20 +43,52 @@ fn runnable(db: &RootDatabase, file_id: FileId, item: &SyntaxNode) -> Option<Run
     }
 }
 
-fn runnable_fn(fn_def: &ast::FnDef) -> Option<Runnable> {
+fn runnable_fn(db: &RootDatabase, file_id: FileId, fn_def: &ast::FnDef) -> Option<Runnable> {
     let name = fn_def.name()?.text();
     let kind = if name == "main" {
         RunnableKind::Bin
     } else if fn_def.has_atom_attr("test") {
-        RunnableKind::Test { name: name.to_string() }
+        let generics = generics_label(db, file_id, fn_def);
+        RunnableKind::Test { name: name.to_string(), generics }
     } else if fn_def.has_atom_attr("bench") {
-        RunnableKind::Bench { name: name.to_string() }
+        let generics = generics_label(db, file_id, fn_def);
+        RunnableKind::Bench { name: name.to_string(), generics }
+    } else if has_doctest(fn_def) {
+        let generics = generics_label(db, file_id, fn_def);
+        let impl_block = fn_def
+            .syntax()
+            .parent()
+            .and_then(|it| it.parent())
+            .and_then(ast::ImplBlock::cast)
+            .and_then(|it| it.target_type());
+        let name = match impl_block {
+            Some(self_ty) => format!("{}::{}", self_ty.syntax().text(), name),
+            None => name.to_string(),
+        };
+        RunnableKind::DocTest { name, generics }
     } else {
         return None;
     };
     Some(Runnable { range: fn_def.syntax().range(), kind })
 }
 
+/// Whether the doc comment of `fn_def` contains a code block.
+fn has_doctest(fn_def: &ast::FnDef) -> bool {
+    fn_def.doc_comment_text().map_or(false, |it| it.contains("```"))
+}
+
+/// The type params of `fn_def` as written in a signature, e.g. `<T, U>`.
+fn generics_label(db: &RootDatabase, file_id: FileId, fn_def: &ast::FnDef) -> Option<String> {
+    let module = hir::source_binder::module_from_child_node(db, file_id, fn_def.syntax())?;
+    let function = hir::source_binder::function_from_module(db, module, fn_def);
+    let generic_params = function.generic_params(db);
+    let params = generic_params.params_including_parent();
+    if params.is_empty() {
+        return None;
+    }
+    Some(format!("<{}>", params.iter().map(|it| it.name()).join(", ")))
+}
+
 fn runnable_mod(db: &RootDatabase, file_id: FileId, module: &ast::Module) -> Option<Runnable> {
     let has_test_function = module
         .item_list()?
@@ -164,4 +203,80 @@ mod tests {
         assert!(runnables.is_empty())
     }
 
+    #[test]
+    fn test_runnables_generic_test_function() {
+        let (analysis, pos) = analysis_and_position(
+            r#"
+        //- /lib.rs
+        <|> //empty
+        #[test]
+        fn test_generic<T>() {}
+        "#,
+        );
+        let runnables = analysis.runnables(pos.file_id).unwrap();
+        assert_debug_snapshot_matches!(&runnables, @r###"[
+    Runnable {
+        range: [1; 40),
+        kind: Test {
+            name: "test_generic",
+            generics: Some(
+                "<T>"
+            )
+        }
+    }
+]"###);
+    }
+
+    #[test]
+    fn test_runnables_doctest_of_generic_function() {
+        let (analysis, pos) = analysis_and_position(
+            r#"
+        //- /lib.rs
+        <|> //empty
+        /// ```
+        /// let x = foo(1);
+        /// ```
+        fn foo<T>(t: T) -> T { t }
+        "#,
+        );
+        let runnables = analysis.runnables(pos.file_id).unwrap();
+        assert_debug_snapshot_matches!(&runnables, @r###"[
+    Runnable {
+        range: [1; 71),
+        kind: DocTest {
+            name: "foo",
+            generics: Some(
+                "<T>"
+            )
+        }
+    }
+]"###);
+    }
+
+    #[test]
+    fn test_runnables_doctest_of_method() {
+        let (analysis, pos) = analysis_and_position(
+            r#"
+        //- /lib.rs
+        <|> //empty
+        struct Foo;
+        impl Foo {
+            /// ```
+            /// Foo.bar();
+            /// ```
+            fn bar(&self) {}
+        }
+        "#,
+        );
+        let runnables = analysis.runnables(pos.file_id).unwrap();
+        assert_debug_snapshot_matches!(&runnables, @r###"[
+    Runnable {
+        range: [36; 95),
+        kind: DocTest {
+            name: "Foo::bar",
+            generics: None
+        }
+    }
+]"###);
+    }
 }
@@ -3,6 +3,7 @@ use flexi_logger::{Duplicate, Logger};
 use gen_lsp_server::{run_server, stdio_transport};
 
 use ra_lsp_server::{Result, InitializationOptions};
+use ra_ide_api::CompletionConfig;
 use ra_prof;
 
 fn main() -> Result<()> {
@@ -51,7 +52,16 @@ fn main_inner() -> Result<()> {
             .and_then(|v| InitializationOptions::deserialize(v).ok())
             .unwrap_or(InitializationOptions::default());
 
-        ra_lsp_server::main_loop(workspace_roots, opts, r, s)
+        let snippet_support = params
+            .capabilities
+            .text_document
+            .and_then(|it| it.completion)
+            .and_then(|it| it.completion_item)
+            .and_then(|it| it.snippet_support);
+        let completion_config =
+            CompletionConfig { snippet_placeholders: snippet_support.unwrap_or(false) };
+
+        ra_lsp_server::main_loop(workspace_roots, opts, completion_config, r, s)
     })?;
     log::info!("shutting down IO...");
     threads.join()?;
@@ -10,7 +10,7 @@ use gen_lsp_server::{
     handle_shutdown, ErrorCode, RawMessage, RawNotification, RawRequest, RawResponse,
 };
 use lsp_types::NumberOrString;
-use ra_ide_api::{Canceled, FileId, LibraryData};
+use ra_ide_api::{Canceled, FileId, LibraryData, CompletionConfig};
 use ra_vfs::VfsTask;
 use rustc_hash::FxHashSet;
 use serde::{de::DeserializeOwned, Serialize};
@@ -50,6 +50,7 @@ const THREADPOOL_SIZE: usize = 8;
 pub fn main_loop(
     ws_roots: Vec<PathBuf>,
     options: InitializationOptions,
+    completion_config: CompletionConfig,
     msg_receiver: &Receiver<RawMessage>,
     msg_sender: &Sender<RawMessage>,
 ) -> Result<()> {
@@ -78,7 +79,7 @@ pub fn main_loop(
         loaded_workspaces
     };
 
-    let mut state = ServerWorldState::new(ws_roots, workspaces);
+    let mut state = ServerWorldState::new(ws_roots, workspaces, completion_config);
 
     log::info!("server initialized, serving requests");
 
@@ -342,9 +342,16 @@ pub fn handle_runnables(
         let r = req::Runnable {
             range: runnable.range.conv_with(&line_index),
             label: match &runnable.kind {
-                RunnableKind::Test { name } => format!("test {}", name),
+                RunnableKind::Test { name, generics } => {
+                    format!("test {}{}", name, generics.as_ref().map_or("", String::as_str))
+                }
                 RunnableKind::TestMod { path } => format!("test-mod {}", path),
-                RunnableKind::Bench { name } => format!("bench {}", name),
+                RunnableKind::Bench { name, generics } => {
+                    format!("bench {}{}", name, generics.as_ref().map_or("", String::as_str))
+                }
+                RunnableKind::DocTest { name, generics } => {
+                    format!("doctest {}{}", name, generics.as_ref().map_or("", String::as_str))
+                }
                 RunnableKind::Bin => "run binary".to_string(),
             },
             bin: "cargo".to_string(),
@@ -423,7 +430,7 @@ pub fn handle_completion(
         return Ok(None);
     }
 
-    let items = match world.analysis().completions(position)? {
+    let items = match world.analysis().completions(position, &world.completion_config)? {
         None => return Ok(None),
         Some(items) => items,
     };
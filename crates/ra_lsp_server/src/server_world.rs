@@ -6,7 +6,7 @@ use std::{
 use lsp_types::Url;
 use ra_ide_api::{
     Analysis, AnalysisChange, AnalysisHost, CrateGraph, FileId, LibraryData,
-    SourceRootId
+    SourceRootId, CompletionConfig,
 };
 use ra_vfs::{Vfs, VfsChange, VfsFile, VfsRoot};
 use relative_path::RelativePathBuf;
@@ -28,16 +28,22 @@ pub struct ServerWorldState {
     pub workspaces: Arc<Vec<ProjectWorkspace>>,
     pub analysis_host: AnalysisHost,
     pub vfs: Arc<RwLock<Vfs>>,
+    pub completion_config: CompletionConfig,
 }
 
 pub struct ServerWorld {
     pub workspaces: Arc<Vec<ProjectWorkspace>>,
     pub analysis: Analysis,
     pub vfs: Arc<RwLock<Vfs>>,
+    pub completion_config: CompletionConfig,
 }
 
 impl ServerWorldState {
-    pub fn new(folder_roots: Vec<PathBuf>, workspaces: Vec<ProjectWorkspace>) -> ServerWorldState {
+    pub fn new(
+        folder_roots: Vec<PathBuf>,
+        workspaces: Vec<ProjectWorkspace>,
+        completion_config: CompletionConfig,
+    ) -> ServerWorldState {
         let mut change = AnalysisChange::new();
 
         let mut roots = Vec::new();
@@ -73,6 +79,7 @@ impl ServerWorldState {
             workspaces: Arc::new(workspaces),
             analysis_host,
             vfs: Arc::new(RwLock::new(vfs)),
+            completion_config,
         }
     }
 
@@ -137,6 +144,7 @@ impl ServerWorldState {
             workspaces: Arc::clone(&self.workspaces),
             analysis: self.analysis_host.analysis(),
             vfs: Arc::clone(&self.vfs),
+            completion_config: self.completion_config.clone(),
         }
     }
 
@@ -21,6 +21,7 @@ use tempfile::TempDir;
 use thread_worker::Worker;
 use test_utils::{parse_fixture, find_mismatch};
 
+use ra_ide_api::CompletionConfig;
 use ra_lsp_server::{
     main_loop, req,
     InitializationOptions,
@@ -93,6 +94,7 @@ impl Server {
                 main_loop(
                     roots,
                     InitializationOptions::default(),
+                    CompletionConfig::default(),
                     &mut msg_receiver,
                     &mut msg_sender,
                 )
@@ -2849,6 +2849,14 @@ impl PathSegment {
     pub fn type_arg_list(&self) -> Option<&TypeArgList> {
         super::child_opt(self)
     }
+
+    pub fn param_list(&self) -> Option<&ParamList> {
+        super::child_opt(self)
+    }
+
+    pub fn ret_type(&self) -> Option<&RetType> {
+        super::child_opt(self)
+    }
 }
 
 // PathType